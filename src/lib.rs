@@ -13,10 +13,20 @@ extern crate alloc;
 extern crate authallocator;
 
 
+pub mod zip_archive;
+pub mod zip_archive_editor;
+pub mod zip_archive_writer;
 pub mod zip_central_directory;
+pub mod zip_compression;
+pub mod zip_crc32;
+pub mod zip_crypto;
 pub mod zip_eocd;
 pub mod zip_error;
+pub mod zip_extra_field;
 pub mod zip_local_file_header;
+pub mod zip_multi_volume;
+pub mod zip_name_index;
+pub mod zip_stream_reader;
 
 
 mod read_ext;
\ No newline at end of file
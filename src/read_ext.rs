@@ -1,6 +1,7 @@
 
+use alloc::vec;
 use alloc::vec::Vec;
-use core2::io::{Take,Read};
+use core2::io::{BufRead,Take,Read};
 use core2::io::{Result,ErrorKind,Error};
 use core::convert::TryFrom;
 
@@ -12,47 +13,118 @@ pub trait ReadExt : Read {
 }
 
 
+/// Maximum number of bytes the buffer of `read_to_end` grows by at a time
+const READ_CHUNK_SIZE: usize = 1 << 16;
+
 impl<T: Read> ReadExt for Take<&mut T> {
 
     fn read_to_end(&mut self, buf: &mut Vec<u8>) -> Result<usize>{
 
-        let limit = usize::try_from(self.limit()).unwrap();
-        buf.reserve(limit+1);
-
-        // This code is "unsafe because we use `.set_len()` to improve performance.
-        // It also relies on `ReadOverwrite`.
-        unsafe {
-            // `std::Vec` doesn't guarantee that capacity will be greater after call to `reserve()`
-            // so we don't rely on it.
-            // "Vec does not guarantee any particular growth strategy when reallocating when full,
-            // nor when reserve is called." - documentation
-            if buf.capacity() > buf.len() {
-                let begin = buf.len();
-                // This is correct in the sense it won't cause UB but the `Vec` will contain
-                // uninitialized bytes. Those bytes will be overwritten by reader thanks to
-                // `ReadOverwrite`
-                let capacity = buf.capacity();
-                buf.set_len(capacity);
-                match self.read(&mut buf[begin..]) {
-                    Ok(bytes) => {
-                        // Check that returned value is correct.
-                        // This could be omitted, since `ReadOverwrite` is `unsafe` but this is
-                        // quite cheap check and avoids serious problems.
-                        assert!(bytes <= capacity - begin);
-                        buf.set_len(begin + bytes);
-                        Ok(bytes)
-                    }
-                    Err(_e) => {
-                        // We have to reset len to previous value if error happens.
-                        buf.set_len(begin);
-                        Err(Error::from(ErrorKind::UnexpectedEof))
-                    }
+        let begin = buf.len();
+        // The limit often comes from a length field of the archive, which may be corrupt or crafted,
+        // so the buffer grows only as the data is actually read instead of reserving the whole limit.
+        // `read` may return fewer bytes than available (e.g. at the boundary of volumes),
+        // so it is called until the end
+        loop {
+            let chunk_size = usize::try_from(self.limit()).unwrap_or(usize::MAX).min(READ_CHUNK_SIZE);
+            if chunk_size == 0 {
+                break;
+            }
+            let end = buf.len();
+            buf.resize(end + chunk_size, 0);
+            match self.read(&mut buf[end..]) {
+                Ok(0) => {
+                    buf.truncate(end);
+                    break;
+                }
+                Ok(bytes) => {
+                    // Check that returned value is correct.
+                    assert!(bytes <= chunk_size);
+                    buf.truncate(end + bytes);
+                }
+                Err(_e) => {
+                    // We have to reset len to previous value if error happens.
+                    buf.truncate(begin);
+                    return Err(Error::from(ErrorKind::UnexpectedEof));
                 }
-            } else {
-                // Fallback for cases where `reserve` reserves nothing.
-                //self.extend_from_reader_slow(reader)
-                Err(Error::from(ErrorKind::Other))
             }
         }
+        Ok(buf.len() - begin)
     }
-}
\ No newline at end of file
+}
+
+//Not available in core2 without the nightly feature
+
+/// Size of the buffer of `BufReader`
+const BUF_READER_CAPACITY: usize = 1 << 13;
+
+/// `BufRead` adapter of a `Read` object
+///
+/// Bytes read ahead into the buffer are lost when it is dropped;
+/// callers that can seek move back by `buffer().len()` bytes.
+pub struct BufReader<R: Read> {
+    inner: R,
+    buf: Vec<u8>,
+    pos: usize,
+    len: usize,
+}
+
+impl<R: Read> BufReader<R> {
+    pub fn new(inner: R) -> Self {
+        return Self {
+            inner,
+            buf: vec![0; BUF_READER_CAPACITY],
+            pos: 0,
+            len: 0,
+        };
+    }
+
+    /// Returns the bytes read ahead from the inner `Read` object and not consumed yet
+    pub fn buffer(&self) -> &[u8] {
+        return &self.buf[self.pos..self.len];
+    }
+}
+
+impl<R: Read> Read for BufReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        // Large reads bypass the buffer if it is empty
+        if self.pos >= self.len && buf.len() >= self.buf.len() {
+            return self.inner.read(buf);
+        }
+        let available = self.fill_buf()?;
+        let n_read = available.len().min(buf.len());
+        buf[..n_read].copy_from_slice(&available[..n_read]);
+        self.consume(n_read);
+        return Ok(n_read);
+    }
+}
+
+impl<R: Read> BufRead for BufReader<R> {
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        if self.pos >= self.len {
+            self.len = self.inner.read(&mut self.buf)?;
+            self.pos = 0;
+        }
+        return Ok(&self.buf[self.pos..self.len]);
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.len);
+    }
+}
+
+//Slice counterparts of `seek` and `take` for zero-copy parsing
+
+/// Returns `bytes[position..]`, or `None` if `position` is out of range
+pub fn slice_from(bytes: &[u8], position: u64) -> Option<&[u8]> {
+    let position = usize::try_from(position).ok()?;
+    return bytes.get(position..);
+}
+
+/// Splits the first `length` bytes (fewer if `read` is shorter) off `read` and returns them
+pub fn take_slice<'a>(read: &mut &'a [u8], length: u64) -> &'a [u8] {
+    let length = usize::try_from(length).unwrap_or(usize::MAX).min(read.len());
+    let (taken, rest) = read.split_at(length);
+    *read = rest;
+    return taken;
+}
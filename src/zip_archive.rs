@@ -0,0 +1,289 @@
+use super::zip_central_directory::ZipCDEntry;
+use super::zip_compression::ZipDecompressReader;
+use super::zip_eocd::ZipEOCD;
+use super::zip_error::ZipReadError;
+use super::zip_local_file_header::ZipLocalFileHeader;
+use super::zip_multi_volume::ZipMultiVolumeReader;
+use super::zip_name_index::ZipNameIndex;
+use byteorder::ReadBytesExt;
+
+use core::cell::RefCell;
+use core2::io::Take;
+use alloc::vec::Vec;
+
+/// ZIPアーカイブ全体を表す構造体 /
+/// Structure that represents a whole ZIP archive
+///
+/// The EOCD and central directory are parsed once when it is opened.
+/// Local file headers are read lazily from the entries.
+pub struct ZipArchive<R: ReadBytesExt + core2::io::Seek> {
+    /// file handler
+    read: RefCell<R>,
+    /// EOCD of the archive
+    eocd: ZipEOCD,
+    /// central directory entries in the order of the archive
+    cd_list: Vec<ZipCDEntry>,
+    /// index of `cd_list` by file name
+    name_index: ZipNameIndex,
+}
+
+impl<R: ReadBytesExt + core2::io::Seek> ZipArchive<R> {
+    /// Opens a ZIP archive: reads the EOCD and the central directory
+    ///
+    /// # Arguments
+    ///
+    /// * `read` - file handler
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError` if the archive is broken or not supported.
+    pub fn new(mut read: R) -> Result<Self, ZipReadError> {
+        let eocd = ZipEOCD::from_reader(&mut read)?;
+        eocd.check_unsupported_zip_type()?;
+        let cd_list = ZipCDEntry::all_from_eocd(&mut read, &eocd)?;
+        return Ok(Self::from_central_directory(read, eocd, cd_list));
+    }
+
+    /// Generates an archive from the EOCD and central directory already read
+    fn from_central_directory(read: R, eocd: ZipEOCD, cd_list: Vec<ZipCDEntry>) -> Self {
+        let name_index = ZipNameIndex::new(&cd_list);
+        return Self {
+            read: RefCell::new(read),
+            eocd,
+            cd_list,
+            name_index,
+        };
+    }
+
+    /// Returns the number of entries
+    pub fn len(&self) -> usize {
+        return self.cd_list.len();
+    }
+
+    /// Returns `true` if the archive has no entries
+    pub fn is_empty(&self) -> bool {
+        return self.cd_list.is_empty();
+    }
+
+    /// Returns the EOCD
+    pub fn eocd(&self) -> &ZipEOCD {
+        return &self.eocd;
+    }
+
+    /// Returns all the central directory entries
+    pub fn central_directories(&self) -> &[ZipCDEntry] {
+        return &self.cd_list;
+    }
+
+    /// Returns the `index`-th (0-based) entry, or `None` if out of range
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - index of the entry
+    pub fn by_index(&self, index: usize) -> Option<ZipArchiveEntry<'_, R>> {
+        if index >= self.cd_list.len() {
+            return None;
+        }
+        return Some(ZipArchiveEntry {
+            archive: self,
+            index,
+        });
+    }
+
+    /// Returns the index by file name
+    pub fn name_index(&self) -> &ZipNameIndex {
+        return &self.name_index;
+    }
+
+    /// Returns the first entry whose file name is `name`, or `None` if not found
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - byte sequence of the file name
+    pub fn by_name(&self, name: &[u8]) -> Option<ZipArchiveEntry<'_, R>> {
+        return self.by_index(self.name_index.find(name)?);
+    }
+
+    /// Returns the first entry whose file name is `name` ignoring ASCII case, or `None` if not found
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - byte sequence of the file name
+    pub fn by_name_case_insensitive(&self, name: &[u8]) -> Option<ZipArchiveEntry<'_, R>> {
+        return self.by_index(self.name_index.find_case_insensitive(name)?);
+    }
+
+    /// Returns the entries whose file names start with `prefix`, in the order of file names
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - byte sequence of the prefix
+    pub fn by_prefix(&self, prefix: &[u8]) -> Vec<ZipArchiveEntry<'_, R>> {
+        return self
+            .name_index
+            .find_by_prefix(prefix)
+            .into_iter()
+            .filter_map(|index| self.by_index(index))
+            .collect();
+    }
+
+    /// Returns the entries directly in the directory `directory`, in the order of file names
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - byte sequence of the directory path; see `ZipNameIndex::list_directory`
+    pub fn list_directory(&self, directory: &[u8]) -> Vec<ZipArchiveEntry<'_, R>> {
+        return self
+            .name_index
+            .list_directory(directory)
+            .into_iter()
+            .filter_map(|index| self.by_index(index))
+            .collect();
+    }
+
+    /// Returns an iterator over the entries
+    pub fn entries(&self) -> ZipArchiveEntries<'_, R> {
+        return ZipArchiveEntries {
+            archive: self,
+            index: 0,
+        };
+    }
+
+    /// Returns the file handler
+    pub fn into_inner(self) -> R {
+        return self.read.into_inner();
+    }
+}
+
+impl<R: ReadBytesExt + core2::io::Seek> ZipArchive<ZipMultiVolumeReader<R>> {
+    /// Opens a split or spanned ZIP archive from its volumes
+    ///
+    /// The positions in the EOCD and central directory are converted into positions in the joined volumes;
+    /// see `ZipMultiVolumeReader::read_central_directory`.
+    ///
+    /// # Arguments
+    ///
+    /// * `volumes` - file handlers of the volumes in the order of the disk numbers (e.g. `.z01`, `.z02`, ..., `.zip`)
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError` if the archive is broken or not supported.
+    pub fn new_multi_volume(volumes: Vec<R>) -> Result<Self, ZipReadError> {
+        let mut read = ZipMultiVolumeReader::new(volumes)?;
+        let (eocd, cd_list) = read.read_central_directory()?;
+        return Ok(Self::from_central_directory(read, eocd, cd_list));
+    }
+}
+
+/// An entry of `ZipArchive`
+///
+/// The local file header is read from the archive only when requested.
+pub struct ZipArchiveEntry<'a, R: ReadBytesExt + core2::io::Seek> {
+    archive: &'a ZipArchive<R>,
+    index: usize,
+}
+
+impl<'a, R: ReadBytesExt + core2::io::Seek> ZipArchiveEntry<'a, R> {
+    /// Returns the 0-based index of this entry
+    pub fn index(&self) -> usize {
+        return self.index;
+    }
+
+    /// Returns the central directory entry of this entry
+    pub fn central_directory(&self) -> &'a ZipCDEntry {
+        return &self.archive.cd_list[self.index];
+    }
+
+    /// Returns the byte sequence of the file name
+    pub fn file_name_raw(&self) -> &'a [u8] {
+        return &self.central_directory().file_name_raw;
+    }
+
+    /// Reads the local file header (and the file content) of this entry
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError` if reading fails or the local file header is broken.
+    pub fn local_file_header(&self) -> Result<ZipLocalFileHeader<'static>, ZipReadError> {
+        let mut read = self.archive.read.borrow_mut();
+        return ZipLocalFileHeader::from_central_directory(&mut *read, self.central_directory());
+    }
+
+    /// Reads only the local file header of this entry without loading the file content
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError` if reading fails or the local file header is broken.
+    pub fn local_file_header_without_data(
+        &self,
+    ) -> Result<ZipLocalFileHeader<'static>, ZipReadError> {
+        let mut read = self.archive.read.borrow_mut();
+        return ZipLocalFileHeader::header_from_central_directory(
+            &mut *read,
+            self.central_directory(),
+        );
+    }
+
+    /// Streams the decompressed file content to `f` without loading the whole content into memory
+    ///
+    /// The archive cannot be read elsewhere while `f` runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - function that receives the decompressing `Read` object
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError` if reading the local file header fails or the compression method is not supported,
+    /// or the error returned by `f`.
+    pub fn with_decompressed_reader<F, T>(&self, f: F) -> Result<T, ZipReadError>
+    where
+        F: FnOnce(&mut ZipDecompressReader<Take<&mut R>>) -> Result<T, ZipReadError>,
+    {
+        let mut read = self.archive.read.borrow_mut();
+        let local_file_header = ZipLocalFileHeader::header_from_central_directory(
+            &mut *read,
+            self.central_directory(),
+        )?;
+        let mut reader = local_file_header.decompressed_data_reader_from(&mut *read)?;
+        return f(&mut reader);
+    }
+
+    /// Reads the local file header and returns the decompressed file content
+    ///
+    /// # Errors
+    ///
+    /// See `ZipLocalFileHeader::decompress`.
+    pub fn decompress(&self) -> Result<Vec<u8>, ZipReadError> {
+        return self.local_file_header()?.decompress();
+    }
+
+    /// Reads the local file header and returns the decrypted and decompressed file content
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - byte sequence of the password (ignored if the file content is not encrypted)
+    ///
+    /// # Errors
+    ///
+    /// See `ZipLocalFileHeader::decompress_with_password`.
+    pub fn decompress_with_password(&self, password: &[u8]) -> Result<Vec<u8>, ZipReadError> {
+        return self.local_file_header()?.decompress_with_password(password);
+    }
+}
+
+/// Iterator over the entries of `ZipArchive`
+pub struct ZipArchiveEntries<'a, R: ReadBytesExt + core2::io::Seek> {
+    archive: &'a ZipArchive<R>,
+    index: usize,
+}
+
+impl<'a, R: ReadBytesExt + core2::io::Seek> Iterator for ZipArchiveEntries<'a, R> {
+    type Item = ZipArchiveEntry<'a, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = self.archive.by_index(self.index)?;
+        self.index += 1;
+        return Some(entry);
+    }
+}
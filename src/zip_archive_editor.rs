@@ -0,0 +1,258 @@
+use super::zip_archive_writer::{ZipArchiveWriter, ZipEntryOptions};
+use super::zip_central_directory::ZipCDEntry;
+use super::zip_eocd::ZipEOCD;
+use super::zip_error::ZipReadError;
+use byteorder::{ReadBytesExt, WriteBytesExt};
+
+use alloc::vec::Vec;
+
+/// An entry of `ZipArchiveEditor`
+enum ZipEditorEntry {
+    /// Entry of the source archive copied without recompressing
+    Original {
+        cd: ZipCDEntry,
+        /// file name in the new archive
+        name: Vec<u8>,
+    },
+    /// File added or replaced
+    File {
+        name: Vec<u8>,
+        data: Vec<u8>,
+        options: ZipEntryOptions,
+    },
+    /// Directory added
+    Directory {
+        name: Vec<u8>,
+        options: ZipEntryOptions,
+    },
+}
+
+impl ZipEditorEntry {
+    fn name(&self) -> &[u8] {
+        return match self {
+            ZipEditorEntry::Original { name, .. } => name,
+            ZipEditorEntry::File { name, .. } => name,
+            ZipEditorEntry::Directory { name, .. } => name,
+        };
+    }
+}
+
+/// 既存のZIPアーカイブを編集して新しいアーカイブを書き出す構造体 /
+/// Structure that edits an existing ZIP archive and writes the result as a new archive
+///
+/// Renames, deletions, replacements and additions are recorded, and applied by `write_to`.
+/// Entries that are not replaced are copied with their compressed data verbatim (see `ZipArchiveWriter::copy_entry`).
+/// Entries are looked up by file name; if there are multiple entries with the same name, the first one is used.
+pub struct ZipArchiveEditor<R: ReadBytesExt + core2::io::Seek> {
+    /// file handler of the source archive
+    read: R,
+    /// entries in the order of the new archive
+    entries: Vec<ZipEditorEntry>,
+    /// ZIP file comment
+    comment: Vec<u8>,
+}
+
+impl<R: ReadBytesExt + core2::io::Seek> ZipArchiveEditor<R> {
+    /// Opens a ZIP archive to edit: reads the EOCD and the central directory
+    ///
+    /// # Arguments
+    ///
+    /// * `read` - file handler of the source archive
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError` if the archive is broken or not supported.
+    pub fn new(mut read: R) -> Result<Self, ZipReadError> {
+        let eocd = ZipEOCD::from_reader(&mut read)?;
+        eocd.check_unsupported_zip_type()?;
+        let entries = ZipCDEntry::all_from_eocd(&mut read, &eocd)?
+            .into_iter()
+            .map(|cd| ZipEditorEntry::Original {
+                name: cd.file_name_raw.clone(),
+                cd,
+            })
+            .collect();
+        return Ok(Self {
+            read,
+            entries,
+            comment: eocd.comment,
+        });
+    }
+
+    /// Returns the number of entries after the edits so far
+    pub fn len(&self) -> usize {
+        return self.entries.len();
+    }
+
+    /// Returns `true` if no entries remain
+    pub fn is_empty(&self) -> bool {
+        return self.entries.is_empty();
+    }
+
+    /// Returns the file names after the edits so far, in the order of the new archive
+    pub fn file_names(&self) -> Vec<&[u8]> {
+        return self.entries.iter().map(|entry| entry.name()).collect();
+    }
+
+    /// Returns `true` if there is an entry whose file name is `name`
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - byte sequence of the file name
+    pub fn contains(&self, name: &[u8]) -> bool {
+        return self.position(name).is_some();
+    }
+
+    /// Renames an entry and returns `true`, or returns `false` if not found or `new_name` is already used by another entry
+    ///
+    /// The file name in both the local file header and central directory is replaced.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - byte sequence of the current file name
+    /// * `new_name` - byte sequence of the new file name
+    pub fn rename(&mut self, name: &[u8], new_name: &[u8]) -> bool {
+        let index = match self.position(name) {
+            Some(index) => index,
+            None => return false,
+        };
+        if self.position(new_name).map_or(false, |other| other != index) {
+            return false;
+        }
+        match &mut self.entries[index] {
+            ZipEditorEntry::Original { name, .. } => *name = new_name.to_vec(),
+            ZipEditorEntry::File { name, .. } => *name = new_name.to_vec(),
+            ZipEditorEntry::Directory { name, .. } => *name = new_name.to_vec(),
+        }
+        return true;
+    }
+
+    /// Deletes an entry and returns `true`, or returns `false` if not found
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - byte sequence of the file name
+    pub fn remove(&mut self, name: &[u8]) -> bool {
+        return match self.position(name) {
+            Some(index) => {
+                self.entries.remove(index);
+                true
+            }
+            None => false,
+        };
+    }
+
+    /// Replaces the content of an entry at the same place and returns `true`, or returns `false` if not found
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - byte sequence of the file name
+    /// * `data` - new file content
+    /// * `options` - options of the new entry (the attributes of the old entry are not inherited)
+    pub fn replace(&mut self, name: &[u8], data: Vec<u8>, options: &ZipEntryOptions) -> bool {
+        let index = match self.position(name) {
+            Some(index) => index,
+            None => return false,
+        };
+        self.entries[index] = ZipEditorEntry::File {
+            name: name.to_vec(),
+            data,
+            options: options.clone(),
+        };
+        return true;
+    }
+
+    /// Adds a file at the end and returns `true`, or returns `false` if `name` is already used
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - byte sequence of the file name (`/` as the separator)
+    /// * `data` - file content
+    /// * `options` - options of the entry
+    pub fn add_file(&mut self, name: &[u8], data: Vec<u8>, options: &ZipEntryOptions) -> bool {
+        if self.contains(name) {
+            return false;
+        }
+        self.entries.push(ZipEditorEntry::File {
+            name: name.to_vec(),
+            data,
+            options: options.clone(),
+        });
+        return true;
+    }
+
+    /// Adds a directory at the end and returns `true`, or returns `false` if `name` is already used
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - byte sequence of the directory name (`/` is appended if missing)
+    /// * `options` - options of the entry
+    pub fn add_directory(&mut self, name: &[u8], options: &ZipEntryOptions) -> bool {
+        let mut directory_name = name.to_vec();
+        if directory_name.last() != Some(&b'/') {
+            directory_name.push(b'/');
+        }
+        if self.contains(&directory_name) {
+            return false;
+        }
+        self.entries.push(ZipEditorEntry::Directory {
+            name: directory_name,
+            options: options.clone(),
+        });
+        return true;
+    }
+
+    /// Sets the ZIP file comment (the comment of the source archive is kept by default)
+    ///
+    /// # Arguments
+    ///
+    /// * `comment` - Slice of the comment (65535 bytes at most)
+    pub fn set_comment_from_slice(&mut self, comment: &[u8]) {
+        self.comment = comment.to_vec();
+    }
+
+    /// Writes the edited archive to `write` and returns it
+    ///
+    /// The source archive is not modified; `write` must be another file.
+    ///
+    /// # Arguments
+    ///
+    /// * `write` - file handler of the new archive (must be empty)
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError` if reading an entry of the source archive fails or it is broken,
+    /// or `ZipReadError::IOError` if writing fails.
+    pub fn write_to<W: WriteBytesExt>(&mut self, write: W) -> Result<W, ZipReadError> {
+        let mut writer = ZipArchiveWriter::new(write);
+        writer.set_comment_from_slice(&self.comment);
+        for entry in &self.entries {
+            match entry {
+                ZipEditorEntry::Original { cd, name } => {
+                    writer.copy_entry(&mut self.read, cd, name)?;
+                }
+                ZipEditorEntry::File {
+                    name,
+                    data,
+                    options,
+                } => {
+                    writer.add_file(name, data, options)?;
+                }
+                ZipEditorEntry::Directory { name, options } => {
+                    writer.add_directory(name, options)?;
+                }
+            }
+        }
+        return Ok(writer.finish()?);
+    }
+
+    /// Returns the file handler of the source archive
+    pub fn into_inner(self) -> R {
+        return self.read;
+    }
+
+    /// Returns the index in `entries` of the first entry whose file name is `name`
+    fn position(&self, name: &[u8]) -> Option<usize> {
+        return self.entries.iter().position(|entry| entry.name() == name);
+    }
+}
@@ -0,0 +1,971 @@
+use super::zip_central_directory::{
+    ZipCDEntry, DATA_DESCRIPTOR_EXISTS_FLAG_BIT, DATA_ENCRYPTED_FLAG_BIT,
+    LZMA_END_OF_STREAM_MARKER_FLAG_BIT, UTF8_FLAG_BIT,
+};
+use super::zip_compression::{
+    compress, ZipCompressWriter, COMPRESSION_METHOD_BZIP2, COMPRESSION_METHOD_DEFLATE,
+    COMPRESSION_METHOD_LZMA, COMPRESSION_METHOD_STORED, COMPRESSION_METHOD_XZ,
+    COMPRESSION_METHOD_ZSTD, DEFAULT_COMPRESSION_LEVEL, DEFAULT_ZSTD_COMPRESSION_LEVEL,
+};
+use super::zip_crc32::crc32;
+use super::zip_crypto::{
+    ZipAesCipher, AES_AUTHENTICATION_CODE_SIZE, AES_VERSION_REQUIRED_TO_EXTRACT, COMPRESSION_METHOD_AES,
+};
+use super::zip_eocd::ZipEOCD;
+use super::zip_error::ZipReadError;
+use super::zip_extra_field::{
+    AesExtraField, Zip64ExtendedInformation, ZipExtraFieldBuilder, ZIP64_VERSION_REQUIRED_TO_EXTRACT,
+};
+use super::zip_local_file_header::{ZipDataDescriptor, ZipLocalFileHeader};
+use byteorder::{ReadBytesExt, WriteBytesExt};
+
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use alloc::format;
+use core2::io::{Error, ErrorKind, Read, Write};
+
+/// MS-DOS date of 1980-01-01, the earliest date that can be represented
+const DEFAULT_LAST_MOD_DATE: u16 = (1 << 5) | 1;
+/// Version needed to extract stored files
+const VERSION_REQUIRED_TO_EXTRACT_STORED: u16 = 10;
+/// Version needed to extract directories and deflated files
+const VERSION_REQUIRED_TO_EXTRACT_DEFLATE: u16 = 20;
+/// Version needed to extract bzip2-compressed files
+const VERSION_REQUIRED_TO_EXTRACT_BZIP2: u16 = 46;
+/// Version needed to extract LZMA- and XZ-compressed files
+const VERSION_REQUIRED_TO_EXTRACT_LZMA: u16 = 63;
+/// Version needed to extract Zstandard-compressed files
+const VERSION_REQUIRED_TO_EXTRACT_ZSTD: u16 = 63;
+/// Size of the buffer to copy compressed data
+const COPY_BUFFER_SIZE: usize = 1 << 16;
+/// WinZip AES vendor version written by the writer (AE-2: CRC-32 is not stored)
+const AES_VENDOR_VERSION: u16 = 2;
+/// Signature at the start of the first volume of a split archive (same as the one of data descriptors)
+const SPANNING_SIGNATURE: [u8; 4] = [0x50, 0x4b, 0x7, 0x8];
+
+/// WinZip AES encryption settings of `ZipEntryOptions`
+#[derive(Clone)]
+struct ZipAesEncryption {
+    password: Vec<u8>,
+    /// 1: AES-128, 2: AES-192, 3: AES-256
+    strength: u8,
+    salt: Vec<u8>,
+}
+
+/// Options of an entry added to `ZipArchiveWriter`
+#[derive(Clone)]
+pub struct ZipEntryOptions {
+    /// `COMPRESSION_METHOD_STORED`, `COMPRESSION_METHOD_DEFLATE`, `COMPRESSION_METHOD_BZIP2`,
+    /// `COMPRESSION_METHOD_LZMA`, `COMPRESSION_METHOD_ZSTD` or `COMPRESSION_METHOD_XZ`
+    compression_method: u16,
+    /// compression level (0 - 9); the default level of the compression method if `None`
+    compression_level: Option<u8>,
+    /// MS-DOS time of the last modification
+    last_mod_time: u16,
+    /// MS-DOS date of the last modification
+    last_mod_date: u16,
+    /// `true` if the file name & comment are encoded in UTF-8
+    utf8: bool,
+    /// Byte sequence of extra field (both in the local file header and central directory)
+    extra_field: Vec<u8>,
+    /// File comment
+    file_comment: Vec<u8>,
+    /// See 4.4.15 in https://pkware.cachefly.net/webdocs/casestudies/APPNOTE.TXT
+    external_file_attributes: Option<u32>,
+    /// `true` to write the optional signature of data descriptors of streamed entries
+    data_descriptor_signature: bool,
+    /// `true` if streamed entries may be 4 GiB or more (ZIP64 extended information extra field and data descriptor)
+    large_file: bool,
+    /// WinZip AES encryption of the file content (not encrypted if `None`)
+    aes_encryption: Option<ZipAesEncryption>,
+}
+
+impl ZipEntryOptions {
+    /// Generates default options: deflated with the default level, 1980-01-01 00:00:00, UTF-8 file name, no extra field and no comment
+    pub fn new() -> Self {
+        return Self {
+            compression_method: COMPRESSION_METHOD_DEFLATE,
+            compression_level: None,
+            last_mod_time: 0,
+            last_mod_date: DEFAULT_LAST_MOD_DATE,
+            utf8: true,
+            extra_field: vec![],
+            file_comment: vec![],
+            external_file_attributes: None,
+            data_descriptor_signature: true,
+            large_file: false,
+            aes_encryption: None,
+        };
+    }
+
+    /// Sets the compression method; directories are always stored
+    ///
+    /// # Arguments
+    ///
+    /// * `compression_method` - `COMPRESSION_METHOD_STORED`, `COMPRESSION_METHOD_DEFLATE`, `COMPRESSION_METHOD_BZIP2`,
+    ///   `COMPRESSION_METHOD_LZMA`, `COMPRESSION_METHOD_ZSTD` or `COMPRESSION_METHOD_XZ`
+    pub fn compression_method(mut self, compression_method: u16) -> Self {
+        self.compression_method = compression_method;
+        return self;
+    }
+
+    /// Sets the compression level for deflate, bzip2, LZMA, Zstandard and XZ
+    ///
+    /// The default level is `DEFAULT_COMPRESSION_LEVEL` (`DEFAULT_ZSTD_COMPRESSION_LEVEL` for Zstandard).
+    ///
+    /// # Arguments
+    ///
+    /// * `compression_level` - 0 (no compression) - 9 (best compression), 0 - 22 for Zstandard;
+    ///   see `ZipCompressWriter::new` (Zstandard levels 1 - 22 all compress at the fastest level)
+    pub fn compression_level(mut self, compression_level: u8) -> Self {
+        self.compression_level = Some(compression_level);
+        return self;
+    }
+
+    /// Returns the compression level passed to the compressor
+    fn effective_compression_level(&self) -> u8 {
+        return match (self.compression_level, self.compression_method) {
+            (Some(compression_level), _) => compression_level,
+            (None, COMPRESSION_METHOD_ZSTD) => DEFAULT_ZSTD_COMPRESSION_LEVEL,
+            (None, _) => DEFAULT_COMPRESSION_LEVEL,
+        };
+    }
+
+    /// Sets the last modification date and time in MS-DOS format
+    ///
+    /// # Arguments
+    ///
+    /// * `last_mod_date` - MS-DOS date
+    /// * `last_mod_time` - MS-DOS time
+    pub fn last_modified(mut self, last_mod_date: u16, last_mod_time: u16) -> Self {
+        self.last_mod_date = last_mod_date;
+        self.last_mod_time = last_mod_time;
+        return self;
+    }
+
+    /// Sets whether bit #11 of general purpose bit flag (file name & comment in UTF-8) is set
+    ///
+    /// # Arguments
+    ///
+    /// * `utf8` - `false` if the file name is encoded in a legacy encoding (e.g. CP437)
+    pub fn utf8(mut self, utf8: bool) -> Self {
+        self.utf8 = utf8;
+        return self;
+    }
+
+    /// Sets the extra field (e.g. built by `ZipExtraFieldBuilder`)
+    ///
+    /// ZIP64 extended information extra field (0x0001) is managed by the writer and must not be included.
+    ///
+    /// # Arguments
+    ///
+    /// * `extra_field` - byte sequence of extra field
+    pub fn extra_field(mut self, extra_field: Vec<u8>) -> Self {
+        self.extra_field = extra_field;
+        return self;
+    }
+
+    /// Sets the file comment
+    ///
+    /// # Arguments
+    ///
+    /// * `file_comment` - comment encoded in the same encoding as the file name
+    pub fn file_comment(mut self, file_comment: Vec<u8>) -> Self {
+        self.file_comment = file_comment;
+        return self;
+    }
+
+    /// Sets the external file attributes (e.g. `0o100644 << 16` for a Unix regular file)
+    ///
+    /// If not set, only the MS-DOS directory flag is set for directories.
+    ///
+    /// # Arguments
+    ///
+    /// * `external_file_attributes` - see 4.4.15 in https://pkware.cachefly.net/webdocs/casestudies/APPNOTE.TXT
+    pub fn external_file_attributes(mut self, external_file_attributes: u32) -> Self {
+        self.external_file_attributes = Some(external_file_attributes);
+        return self;
+    }
+
+    /// Sets whether the data descriptor of an entry streamed by `ZipArchiveWriter::start_file` has the optional signature (PK\x07\x08)
+    ///
+    /// The signature is written by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `data_descriptor_signature` - `false` to omit the signature
+    pub fn data_descriptor_signature(mut self, data_descriptor_signature: bool) -> Self {
+        self.data_descriptor_signature = data_descriptor_signature;
+        return self;
+    }
+
+    /// Sets whether an entry streamed by `ZipArchiveWriter::start_file` may be 4 GiB or more
+    ///
+    /// If set, the local file header has ZIP64 extended information extra field with zero sizes,
+    /// and the data descriptor has 8-byte sizes (4.3.9.2 in https://pkware.cachefly.net/webdocs/casestudies/APPNOTE.TXT).
+    /// Otherwise, writing fails before the compressed or uncompressed size reaches 4 GiB.
+    /// Entries added by `add_file` get ZIP64 structures automatically regardless of this option.
+    ///
+    /// # Arguments
+    ///
+    /// * `large_file` - `true` to allow streamed entries of 4 GiB or more
+    pub fn large_file(mut self, large_file: bool) -> Self {
+        self.large_file = large_file;
+        return self;
+    }
+
+    /// Encrypts the file content with WinZip AES (AE-2)
+    ///
+    /// The salt must be random and must not be reused with the same password;
+    /// it is taken from the caller because this crate has no random number generator.
+    /// Options with the same salt must not be shared between entries.
+    /// Adding an entry with this option fails with `ErrorKind::InvalidInput` if `aes` feature is disabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - byte sequence of the password
+    /// * `strength` - 1: AES-128, 2: AES-192, 3: AES-256
+    /// * `salt` - random bytes (8, 12 and 16 bytes for strength 1, 2 and 3 respectively)
+    pub fn aes_encryption(mut self, password: &[u8], strength: u8, salt: &[u8]) -> Self {
+        self.aes_encryption = Some(ZipAesEncryption {
+            password: password.to_vec(),
+            strength,
+            salt: salt.to_vec(),
+        });
+        return self;
+    }
+}
+
+impl Default for ZipEntryOptions {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+/// Writer that builds a ZIP archive from scratch (or appends entries to an existing one; see `append`)
+///
+/// Local file headers are written sequentially as entries are added,
+/// and the central directory and EOCD are written by `finish`.
+/// Offsets, lengths and ZIP64 structures are computed automatically.
+/// The output can be split into volumes of a maximum size; see `new_split`.
+pub struct ZipArchiveWriter<W: WriteBytesExt> {
+    /// file handler (of the current volume if split)
+    write: W,
+    /// position of the next byte to be written in the current volume (**absolute** unless split)
+    position: u64,
+    /// number of the current volume (0-based; always 0 unless split)
+    disk_index: u32,
+    /// state of split output (`None` for a single archive)
+    split: Option<ZipSplitState<W>>,
+    /// central directory entries of the entries written so far
+    cd_list: Vec<ZipCDEntry>,
+    /// ZIP file comment
+    comment: Vec<u8>,
+}
+
+/// State of `ZipArchiveWriter` writing a split archive
+struct ZipSplitState<W> {
+    /// maximum size of a volume
+    segment_size: u64,
+    /// opens the volume of the given disk number
+    new_volume: Box<dyn FnMut(u32) -> core2::io::Result<W>>,
+    /// volumes already filled, in the order of the disk numbers
+    finished_volumes: Vec<W>,
+}
+
+impl<W: WriteBytesExt> ZipArchiveWriter<W> {
+    /// Generates a writer that writes a new ZIP archive to `write`
+    ///
+    /// # Arguments
+    ///
+    /// * `write` - file handler (must be empty; the archive starts at the position 0)
+    pub fn new(write: W) -> Self {
+        return Self {
+            write,
+            position: 0,
+            disk_index: 0,
+            split: None,
+            cd_list: vec![],
+            comment: vec![],
+        };
+    }
+
+    /// Generates a writer that writes a split archive into volumes of at most `segment_size` bytes
+    ///
+    /// The first volume starts with the spanning signature (PK\x07\x08).
+    /// File contents may span volumes, but headers, central directory entries and EOCD are kept in one volume,
+    /// so volumes other than the last may be a little smaller than `segment_size`.
+    /// Disk numbers and offsets in the disk are recorded in the central directory and EOCD.
+    /// Following the usual naming, disk `n` is stored as `.z{n + 1:02}` and the last one as `.zip`,
+    /// but the total number of volumes is known only after `finish_volumes`.
+    ///
+    /// # Arguments
+    ///
+    /// * `segment_size` - maximum size of a volume (must be large enough to hold any header and EOCD, e.g. 64 KiB)
+    /// * `new_volume` - function that opens the (empty) volume of the given disk number (0-based)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if opening or writing the first volume fails.
+    pub fn new_split<F: FnMut(u32) -> core2::io::Result<W> + 'static>(
+        segment_size: u64,
+        mut new_volume: F,
+    ) -> core2::io::Result<Self> {
+        let write = new_volume(0)?;
+        let mut result = Self::new(write);
+        result.split = Some(ZipSplitState {
+            segment_size,
+            new_volume: Box::new(new_volume),
+            finished_volumes: vec![],
+        });
+        result.write_record(&SPANNING_SIGNATURE)?;
+        return Ok(result);
+    }
+
+    /// Returns the number of entries added so far
+    pub fn len(&self) -> usize {
+        return self.cd_list.len();
+    }
+
+    /// Returns `true` if no entries have been added
+    pub fn is_empty(&self) -> bool {
+        return self.cd_list.is_empty();
+    }
+
+    /// Sets the ZIP file comment written in EOCD
+    ///
+    /// # Arguments
+    ///
+    /// * `comment` - Slice of the comment (65535 bytes at most)
+    pub fn set_comment_from_slice(&mut self, comment: &[u8]) {
+        self.comment = comment.to_vec();
+    }
+
+    /// Adds a file and writes its local file header and content
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - byte sequence of the file name (`/` as the separator)
+    /// * `data` - file content
+    /// * `options` - options of the entry
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of `ErrorKind::InvalidInput` if the file name, extra field or comment is too long,
+    /// the compression method or level is not supported or the AES strength or salt size is invalid,
+    /// or an error if writing fails.
+    pub fn add_file(
+        &mut self,
+        name: &[u8],
+        data: &[u8],
+        options: &ZipEntryOptions,
+    ) -> core2::io::Result<()> {
+        let mut local_file_header = self.new_local_file_header(name, options)?;
+        set_compression_method(&mut local_file_header, options.compression_method);
+        let compressed_data = match options.compression_method {
+            COMPRESSION_METHOD_STORED => Cow::Borrowed(data),
+            _ => Cow::Owned(compress(data, options.compression_method, options.effective_compression_level())?),
+        };
+        local_file_header.compressed_data = match start_aes_encryption(&mut local_file_header, options)? {
+            Some((mut cipher, mut encrypted_data)) => {
+                let encryption_header_size = encrypted_data.len();
+                encrypted_data.extend_from_slice(&compressed_data);
+                cipher.encrypt(&mut encrypted_data[encryption_header_size..]);
+                encrypted_data.extend_from_slice(&cipher.authentication_code());
+                Cow::Owned(encrypted_data)
+            }
+            None => {
+                local_file_header.crc32 = crc32(data);
+                compressed_data
+            }
+        };
+        local_file_header.set_sizes(
+            local_file_header.compressed_data.len() as u64,
+            data.len() as u64,
+        )?;
+        return self.write_entry(&mut local_file_header, options);
+    }
+
+    /// Starts a file whose content is streamed through the returned `ZipEntryWriter`
+    ///
+    /// Works with non-seekable sinks: bit #3 of general purpose bit flag is set, CRC-32 and sizes in the local file header are 0,
+    /// and a data descriptor follows the content.  Call `ZipEntryWriter::finish` after writing the content;
+    /// an entry dropped before that is not recorded in the central directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - byte sequence of the file name (`/` as the separator)
+    /// * `options` - options of the entry
+    ///
+    /// # Errors
+    ///
+    /// See `add_file`.
+    pub fn start_file(
+        &mut self,
+        name: &[u8],
+        options: &ZipEntryOptions,
+    ) -> core2::io::Result<ZipEntryWriter<'_, W>> {
+        let mut local_file_header = self.new_local_file_header(name, options)?;
+        set_compression_method(&mut local_file_header, options.compression_method);
+        local_file_header.general_purpose_flags |= DATA_DESCRIPTOR_EXISTS_FLAG_BIT;
+        if options.large_file {
+            set_zip64_placeholder(&mut local_file_header)?;
+        }
+        let compressor = ZipCompressWriter::new(
+            Vec::new(),
+            options.compression_method,
+            options.effective_compression_level(),
+        )?;
+        let aes = start_aes_encryption(&mut local_file_header, options)?;
+        let disk_index = self.write_local_file_header(&mut local_file_header)?;
+        let (cipher, encryption_header_size) = match aes {
+            Some((cipher, encryption_header)) => {
+                self.write_bytes(&encryption_header)?;
+                (Some(cipher), encryption_header.len() as u64)
+            }
+            None => (None, 0),
+        };
+        return Ok(ZipEntryWriter {
+            archive: self,
+            local_file_header,
+            disk_index,
+            compressor,
+            cipher,
+            encryption_header_size,
+            options: options.clone(),
+        });
+    }
+
+    /// Adds a directory and writes its local file header
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - byte sequence of the directory name (`/` is appended if missing)
+    /// * `options` - options of the entry
+    ///
+    /// # Errors
+    ///
+    /// See `add_file`.
+    pub fn add_directory(&mut self, name: &[u8], options: &ZipEntryOptions) -> core2::io::Result<()> {
+        let mut directory_name = name.to_vec();
+        if directory_name.last() != Some(&b'/') {
+            directory_name.push(b'/');
+        }
+        let mut local_file_header = self.new_local_file_header(&directory_name, options)?;
+        local_file_header.version_required_to_extract = VERSION_REQUIRED_TO_EXTRACT_DEFLATE;
+        return self.write_entry(&mut local_file_header, options);
+    }
+
+    /// Copies an entry of another archive without recompressing it
+    ///
+    /// The local file header, compressed data and data descriptor are copied as they are except for the file name and position,
+    /// and the central directory entry keeps its comment and attributes.
+    ///
+    /// # Arguments
+    ///
+    /// * `read` - file handler of the source archive
+    /// * `cd` - central directory entry of the entry in the source archive
+    /// * `name` - byte sequence of the file name in this archive (may differ from the source)
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError` if reading the source entry fails or it is broken,
+    /// or `ZipReadError::IOError` if the file name is too long or writing fails.
+    pub fn copy_entry<R: ReadBytesExt + core2::io::Seek>(
+        &mut self,
+        read: &mut R,
+        cd: &ZipCDEntry,
+        name: &[u8],
+    ) -> Result<(), ZipReadError> {
+        if name.len() > u16::MAX as usize {
+            return Err(Error::new(ErrorKind::InvalidInput, "file name is too long").into());
+        }
+        let mut local_file_header = ZipLocalFileHeader::header_from_central_directory(read, cd)?;
+        let mut compressed_data = local_file_header.compressed_data_reader_from(read)?;
+        let data_descriptor = local_file_header.data_descriptor.take();
+        local_file_header.set_file_name_from_slice(&name.to_vec());
+        let disk_index = self.write_local_file_header(&mut local_file_header)?;
+        let mut buf = vec![0; COPY_BUFFER_SIZE];
+        let mut n_copied: u64 = 0;
+        loop {
+            let n_read = compressed_data.read(&mut buf)?;
+            if n_read == 0 {
+                break;
+            }
+            self.write_bytes(&buf[..n_read])?;
+            n_copied += n_read as u64;
+        }
+        if n_copied != cd.effective_compressed_size() {
+            return Err(ZipReadError::InvalidZipArchive {
+                reason: format!(
+                    "compressed size is invalid (expected from length value field: {} / got {}",
+                    cd.effective_compressed_size(),
+                    n_copied
+                ),
+            });
+        }
+        if let Some(data_descriptor) = data_descriptor {
+            let mut bytes = vec![];
+            data_descriptor.write(&mut bytes)?;
+            self.write_record(&bytes)?;
+        }
+        let mut new_cd = cd.clone();
+        new_cd.set_file_name_from_slice(&name.to_vec());
+        new_cd.set_local_header_position(local_file_header.starting_position_with_signature)?;
+        new_cd.set_disk_number_start(disk_index)?;
+        self.cd_list.push(new_cd);
+        return Ok(());
+    }
+
+    /// Writes the central directory and EOCD, and returns the file handler (of the last volume if split)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of `ErrorKind::InvalidInput` if the ZIP file comment is too long,
+    /// or an error if writing fails.
+    pub fn finish(self) -> core2::io::Result<W> {
+        let mut volumes = self.finish_volumes()?;
+        return Ok(volumes.pop().unwrap());
+    }
+
+    /// Writes the central directory and EOCD, and returns the file handlers of all the volumes in the order of the disk numbers
+    ///
+    /// For a single archive, the only file handler is returned.
+    ///
+    /// # Errors
+    ///
+    /// See `finish`.
+    pub fn finish_volumes(mut self) -> core2::io::Result<Vec<W>> {
+        if self.comment.len() > u16::MAX as usize {
+            return Err(Error::new(ErrorKind::InvalidInput, "ZIP file comment is too long"));
+        }
+        let mut cd_start: Option<(u32, u64)> = None;
+        let mut cd_size: u64 = 0;
+        let mut n_cd_entries_in_disk: u64 = 0;
+        let mut cd_list = core::mem::take(&mut self.cd_list);
+        for cd in &mut cd_list {
+            let mut bytes = vec![];
+            cd.write(&mut bytes)?;
+            self.reserve(bytes.len() as u64)?;
+            match cd_start {
+                None => cd_start = Some((self.disk_index, self.position)),
+                Some(_) if self.position == 0 => n_cd_entries_in_disk = 0,
+                Some(_) => {}
+            }
+            cd.starting_position_with_signature = self.position;
+            cd.starting_position_without_signature = self.position + 4;
+            self.write_bytes(&bytes)?;
+            cd_size += bytes.len() as u64;
+            n_cd_entries_in_disk += 1;
+        }
+        self.cd_list = cd_list;
+        let (cd_start_disk_index, cd_starting_position) =
+            cd_start.unwrap_or((self.disk_index, self.position));
+        let mut eocd = self.new_eocd(cd_size, cd_start_disk_index, cd_starting_position, n_cd_entries_in_disk);
+        let eocd_disk_index = self.disk_index;
+        self.reserve(eocd.size())?;
+        if self.disk_index != eocd_disk_index {
+            // no entries of the central directory are on the new volume
+            eocd = self.new_eocd(cd_size, cd_start_disk_index, cd_starting_position, 0);
+        }
+        let mut bytes = vec![];
+        eocd.write(&mut bytes)?;
+        self.write_bytes(&bytes)?;
+        let mut volumes = match self.split {
+            Some(split) => split.finished_volumes,
+            None => vec![],
+        };
+        volumes.push(self.write);
+        return Ok(volumes);
+    }
+
+    /// Generates a local file header without the content from the name and options
+    fn new_local_file_header<'d>(
+        &self,
+        name: &[u8],
+        options: &ZipEntryOptions,
+    ) -> core2::io::Result<ZipLocalFileHeader<'d>> {
+        if name.len() > u16::MAX as usize {
+            return Err(Error::new(ErrorKind::InvalidInput, "file name is too long"));
+        }
+        if options.extra_field.len() > u16::MAX as usize {
+            return Err(Error::new(ErrorKind::InvalidInput, "extra field is too long"));
+        }
+        if options.file_comment.len() > u16::MAX as usize {
+            return Err(Error::new(ErrorKind::InvalidInput, "file comment is too long"));
+        }
+        return Ok(ZipLocalFileHeader {
+            version_required_to_extract: 0,
+            general_purpose_flags: if options.utf8 { UTF8_FLAG_BIT } else { 0 },
+            compression_method: 0,
+            last_mod_time: options.last_mod_time,
+            last_mod_date: options.last_mod_date,
+            crc32: 0,
+            compressed_size: 0,
+            uncompressed_size: 0,
+            file_name_length: name.len() as u16,
+            extra_field_length: options.extra_field.len() as u16,
+            file_name_raw: name.to_vec(),
+            extra_field: options.extra_field.clone(),
+            compressed_data: Cow::Borrowed(&[]),
+            data_descriptor: None,
+            starting_position_with_signature: self.position,
+            starting_position_without_signature: self.position + 4,
+        });
+    }
+
+    /// Generates the EOCD written at the current position
+    fn new_eocd(
+        &self,
+        cd_size: u64,
+        cd_start_disk_index: u32,
+        cd_starting_position: u64,
+        n_cd_entries_in_disk: u64,
+    ) -> ZipEOCD {
+        let mut eocd = ZipEOCD::new_split(
+            self.cd_list.len() as u64,
+            n_cd_entries_in_disk,
+            cd_size,
+            cd_start_disk_index,
+            cd_starting_position,
+            self.disk_index,
+            self.position,
+        );
+        eocd.set_comment_from_slice(&self.comment);
+        return eocd;
+    }
+
+    /// Writes the local file header and records its central directory entry
+    fn write_entry(
+        &mut self,
+        local_file_header: &mut ZipLocalFileHeader,
+        options: &ZipEntryOptions,
+    ) -> core2::io::Result<()> {
+        let disk_index = self.write_local_file_header(local_file_header)?;
+        return self.push_central_directory(local_file_header, disk_index, options);
+    }
+
+    /// Writes the local file header with its content at the current position, updates its position,
+    /// and returns the number of the volume that has the header
+    ///
+    /// The header is kept in one volume if split.
+    fn write_local_file_header(&mut self, local_file_header: &mut ZipLocalFileHeader) -> core2::io::Result<u32> {
+        let compressed_data = core::mem::replace(&mut local_file_header.compressed_data, Cow::Borrowed(&[]));
+        let mut header = vec![];
+        local_file_header.write(&mut header)?;
+        self.reserve(header.len() as u64)?;
+        local_file_header.starting_position_with_signature = self.position;
+        local_file_header.starting_position_without_signature = self.position + 4;
+        let disk_index = self.disk_index;
+        self.write_bytes(&header)?;
+        self.write_bytes(&compressed_data)?;
+        local_file_header.compressed_data = compressed_data;
+        return Ok(disk_index);
+    }
+
+    /// Writes a record (e.g. data descriptor) that must not span volumes
+    fn write_record(&mut self, bytes: &[u8]) -> core2::io::Result<()> {
+        self.reserve(bytes.len() as u64)?;
+        return self.write_bytes(bytes);
+    }
+
+    /// Starts the next volume if a record of `size` bytes does not fit in the rest of the current volume
+    fn reserve(&mut self, size: u64) -> core2::io::Result<()> {
+        let segment_size = match &self.split {
+            Some(split) => split.segment_size,
+            None => return Ok(()),
+        };
+        if size > segment_size {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "a header or EOCD is larger than the segment size",
+            ));
+        }
+        if self.position + size > segment_size {
+            self.start_next_volume()?;
+        }
+        return Ok(());
+    }
+
+    /// Writes bytes at the current position; they are split into volumes if needed
+    fn write_bytes(&mut self, mut bytes: &[u8]) -> core2::io::Result<()> {
+        while !bytes.is_empty() {
+            let length = match self.split.as_ref().map(|split| split.segment_size) {
+                Some(segment_size) => {
+                    if self.position >= segment_size {
+                        self.start_next_volume()?;
+                    }
+                    core::cmp::min(bytes.len() as u64, segment_size - self.position) as usize
+                }
+                None => bytes.len(),
+            };
+            self.write.write_all(&bytes[..length])?;
+            self.position += length as u64;
+            bytes = &bytes[length..];
+        }
+        return Ok(());
+    }
+
+    /// Closes the current volume and opens the next one
+    fn start_next_volume(&mut self) -> core2::io::Result<()> {
+        let split = match &mut self.split {
+            Some(split) => split,
+            None => return Ok(()),
+        };
+        let disk_index = self.disk_index.checked_add(1).ok_or_else(|| {
+            Error::new(ErrorKind::InvalidInput, "too many volumes")
+        })?;
+        let mut write = (split.new_volume)(disk_index)?;
+        core::mem::swap(&mut self.write, &mut write);
+        split.finished_volumes.push(write);
+        self.disk_index = disk_index;
+        self.position = 0;
+        return Ok(());
+    }
+
+    /// Records the central directory entry of a written local file header
+    ///
+    /// `disk_index` is the number of the volume that has the local file header.
+    fn push_central_directory(
+        &mut self,
+        local_file_header: &ZipLocalFileHeader,
+        disk_index: u32,
+        options: &ZipEntryOptions,
+    ) -> core2::io::Result<()> {
+        // the position is fixed in `finish`
+        let mut cd = ZipCDEntry::from_local_file_header(local_file_header, 0)?;
+        cd.set_disk_number_start(disk_index)?;
+        cd.set_file_coment_from_slice(&options.file_comment);
+        if let Some(external_file_attributes) = options.external_file_attributes {
+            cd.external_file_attributes = external_file_attributes;
+        }
+        self.cd_list.push(cd);
+        return Ok(());
+    }
+}
+
+impl<W: ReadBytesExt + WriteBytesExt + core2::io::Seek> ZipArchiveWriter<W> {
+    /// Opens an existing ZIP archive to append entries to it without rewriting the existing entries
+    ///
+    /// New local file headers are written where the old central directory started,
+    /// and `finish` writes the central directory of both the old and new entries and a new EOCD.
+    /// The ZIP file comment is kept unless it is replaced.
+    ///
+    /// The archive does not get shorter unless the comment is shortened;
+    /// otherwise the caller has to truncate the file at the end of the new EOCD.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - file handler of the archive (must be readable and writable)
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError` if the archive is broken or not supported.
+    pub fn append(mut file: W) -> Result<Self, ZipReadError> {
+        let eocd = ZipEOCD::from_reader(&mut file)?;
+        eocd.check_unsupported_zip_type()?;
+        let cd_list = ZipCDEntry::all_from_eocd(&mut file, &eocd)?;
+        let position = file.seek(core2::io::SeekFrom::Start(eocd.effective_cd_starting_position()))?;
+        return Ok(Self {
+            write: file,
+            position,
+            disk_index: 0,
+            split: None,
+            cd_list,
+            comment: eocd.comment,
+        });
+    }
+}
+
+/// `Write` object that streams the content of an entry started by `ZipArchiveWriter::start_file`
+///
+/// Unless `ZipEntryOptions::large_file` is set, writing fails with `ErrorKind::InvalidInput`
+/// before the compressed or uncompressed size reaches 4 GiB, and nothing beyond the limit is written.
+pub struct ZipEntryWriter<'w, W: WriteBytesExt> {
+    archive: &'w mut ZipArchiveWriter<W>,
+    /// local file header already written (without CRC-32 and sizes)
+    local_file_header: ZipLocalFileHeader<'static>,
+    /// number of the volume that has the local file header
+    disk_index: u32,
+    /// compressor whose output is moved to `archive` on every write
+    compressor: ZipCompressWriter<Vec<u8>>,
+    /// cipher that encrypts the output of the compressor (if WinZip AES encryption is enabled)
+    cipher: Option<ZipAesCipher>,
+    /// size of the salt and password verification value already written
+    encryption_header_size: u64,
+    options: ZipEntryOptions,
+}
+
+impl<W: WriteBytesExt> ZipEntryWriter<'_, W> {
+    /// Finishes the content, writes the data descriptor and records the entry in the central directory
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of `ErrorKind::InvalidInput` if the compressed size reaches 4 GiB and `large_file` is not set,
+    /// or an error if compressing or writing fails.
+    pub fn finish(mut self) -> core2::io::Result<()> {
+        self.compressor.finish()?;
+        self.write_compressed()?;
+        let compressed_size = self.compressed_size();
+        let mut crc32 = self.compressor.crc32();
+        if let Some(cipher) = &self.cipher {
+            self.archive.write_bytes(&cipher.authentication_code())?;
+            // AE-2
+            crc32 = 0;
+        }
+        let mut data_descriptor = ZipDataDescriptor::new(
+            crc32,
+            compressed_size,
+            self.compressor.uncompressed_size(),
+            self.options.data_descriptor_signature,
+        );
+        // the local file header has ZIP64 extended information extra field, so readers expect 8-byte sizes
+        data_descriptor.is_zip64 = self.options.large_file;
+        let mut bytes = vec![];
+        data_descriptor.write(&mut bytes)?;
+        self.archive.write_record(&bytes)?;
+        // the central directory has the actual values
+        self.local_file_header.crc32 = data_descriptor.crc32;
+        self.local_file_header
+            .set_sizes(data_descriptor.compressed_size, data_descriptor.uncompressed_size)?;
+        return self
+            .archive
+            .push_central_directory(&self.local_file_header, self.disk_index, &self.options);
+    }
+
+    /// Returns the compressed size so far, including the salt, password verification value and authentication code if encrypted
+    fn compressed_size(&self) -> u64 {
+        let mut compressed_size = self.compressor.compressed_size();
+        if self.cipher.is_some() {
+            compressed_size += self.encryption_header_size + AES_AUTHENTICATION_CODE_SIZE;
+        }
+        return compressed_size;
+    }
+
+    /// Moves the output of the compressor to the archive (encrypting it if needed)
+    ///
+    /// Nothing is written if the compressed size reaches 4 GiB and `large_file` is not set.
+    fn write_compressed(&mut self) -> core2::io::Result<()> {
+        if !self.options.large_file && self.compressed_size() >= u32::MAX as u64 {
+            return Err(too_large_streamed_entry_error());
+        }
+        let mut output = core::mem::take(self.compressor.get_mut());
+        if let Some(cipher) = &mut self.cipher {
+            cipher.encrypt(&mut output);
+        }
+        return self.archive.write_bytes(&output);
+    }
+}
+
+impl<W: WriteBytesExt> Write for ZipEntryWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> core2::io::Result<usize> {
+        if !self.options.large_file && self.compressor.uncompressed_size() + buf.len() as u64 >= u32::MAX as u64 {
+            return Err(too_large_streamed_entry_error());
+        }
+        let n_written = self.compressor.write(buf)?;
+        self.write_compressed()?;
+        return Ok(n_written);
+    }
+
+    fn flush(&mut self) -> core2::io::Result<()> {
+        self.write_compressed()?;
+        return self.archive.write.flush();
+    }
+}
+
+/// Returns the error of a streamed entry that reaches 4 GiB without `ZipEntryOptions::large_file`
+fn too_large_streamed_entry_error() -> Error {
+    return Error::new(
+        ErrorKind::InvalidInput,
+        "streamed entries must be smaller than 4 GiB unless `large_file` is set",
+    );
+}
+
+/// Returns the version needed to extract entries compressed by `compression_method`
+fn version_required_to_extract(compression_method: u16) -> u16 {
+    return match compression_method {
+        COMPRESSION_METHOD_STORED => VERSION_REQUIRED_TO_EXTRACT_STORED,
+        COMPRESSION_METHOD_BZIP2 => VERSION_REQUIRED_TO_EXTRACT_BZIP2,
+        COMPRESSION_METHOD_LZMA | COMPRESSION_METHOD_XZ => VERSION_REQUIRED_TO_EXTRACT_LZMA,
+        COMPRESSION_METHOD_ZSTD => VERSION_REQUIRED_TO_EXTRACT_ZSTD,
+        _ => VERSION_REQUIRED_TO_EXTRACT_DEFLATE,
+    };
+}
+
+/// Sets the compression method, version needed to extract and general purpose bit flag of `local_file_header`
+///
+/// `ZipCompressWriter` ends LZMA data with the end-of-stream marker, which is indicated by bit #1 of the flag.
+fn set_compression_method(local_file_header: &mut ZipLocalFileHeader, compression_method: u16) {
+    local_file_header.compression_method = compression_method;
+    local_file_header.version_required_to_extract = version_required_to_extract(compression_method);
+    if compression_method == COMPRESSION_METHOD_LZMA {
+        local_file_header.general_purpose_flags |= LZMA_END_OF_STREAM_MARKER_FLAG_BIT;
+    }
+}
+
+/// Adds ZIP64 extended information extra field with zero sizes to the local file header of a streamed entry
+///
+/// The size fields are set to `0xFFFFFFFF` so that they refer to the extra field (4.5.3 in APPNOTE);
+/// the actual sizes follow the content in the ZIP64 data descriptor.
+fn set_zip64_placeholder(local_file_header: &mut ZipLocalFileHeader) -> core2::io::Result<()> {
+    let zip64 = Zip64ExtendedInformation {
+        uncompressed_size: Some(0),
+        compressed_size: Some(0),
+        local_header_position: None,
+        disk_number_start: None,
+    };
+    let extra_field = ZipExtraFieldBuilder::from_extra_field(&local_file_header.extra_field)
+        .zip64(&zip64)
+        .build()?;
+    local_file_header.set_extra_field_from_slice(&extra_field)?;
+    local_file_header.compressed_size = u32::MAX;
+    local_file_header.uncompressed_size = u32::MAX;
+    if local_file_header.version_required_to_extract < ZIP64_VERSION_REQUIRED_TO_EXTRACT {
+        local_file_header.version_required_to_extract = ZIP64_VERSION_REQUIRED_TO_EXTRACT;
+    }
+    return Ok(());
+}
+
+/// Sets up WinZip AES encryption of an entry if it is enabled in `options`
+///
+/// Updates the compression method, version needed to extract, general purpose bit flag and extra field of `local_file_header`,
+/// and returns the cipher and the salt & password verification value to write in front of the encrypted data.
+fn start_aes_encryption(
+    local_file_header: &mut ZipLocalFileHeader,
+    options: &ZipEntryOptions,
+) -> core2::io::Result<Option<(ZipAesCipher, Vec<u8>)>> {
+    let aes_encryption = match &options.aes_encryption {
+        Some(aes_encryption) => aes_encryption,
+        None => return Ok(None),
+    };
+    if !cfg!(feature = "aes") {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "WinZip AES encryption is not supported (`aes` feature is disabled)",
+        ));
+    }
+    let (cipher, password_verification_value) = ZipAesCipher::new(
+        &aes_encryption.password,
+        &aes_encryption.salt,
+        aes_encryption.strength,
+    )
+    .map_err(|_| Error::new(ErrorKind::InvalidInput, "AES strength or salt size is invalid"))?;
+    // fails if the caller's extra field leaves no room for WinZip AES extra field
+    let extra_field = ZipExtraFieldBuilder::from_extra_field(&local_file_header.extra_field)
+        .aes(&AesExtraField {
+            vendor_version: AES_VENDOR_VERSION,
+            vendor_id: *b"AE",
+            strength: aes_encryption.strength,
+            compression_method: local_file_header.compression_method,
+        })
+        .build()?;
+    local_file_header.set_extra_field_from_slice(&extra_field)?;
+    local_file_header.compression_method = COMPRESSION_METHOD_AES;
+    local_file_header.version_required_to_extract = AES_VERSION_REQUIRED_TO_EXTRACT;
+    local_file_header.general_purpose_flags |= DATA_ENCRYPTED_FLAG_BIT;
+    let mut encryption_header = aes_encryption.salt.clone();
+    encryption_header.extend_from_slice(&password_verification_value);
+    return Ok(Some((cipher, encryption_header)));
+}
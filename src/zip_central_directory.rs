@@ -1,5 +1,9 @@
-use super::zip_eocd::ZipEOCD;
+use super::zip_eocd::{ZipEOCD, ZipEOCDRef};
 use super::zip_error::ZipReadError;
+use super::zip_extra_field::{
+    extra_field_length, find_extra_field, ZipExtraFieldIterator, replace_extra_field, Zip64ExtendedInformation, ZIP64_EXTRA_FIELD_ID,
+    ZIP64_VERSION_REQUIRED_TO_EXTRACT,
+};
 use super::zip_local_file_header::ZipLocalFileHeader;
 use byteorder::{ReadBytesExt, WriteBytesExt, LE};
 use core2::io::SeekFrom;
@@ -11,19 +15,25 @@ use alloc::string::ToString;
 
 #[cfg(not(feature = "std"))]
 use crate::read_ext::ReadExt;
+use crate::read_ext::{slice_from, take_slice};
 
 /// Magic number of central directory
-const CD_MAGIC: [u8; 4] = [0x50, 0x4b, 0x1, 0x2];
+pub(crate) const CD_MAGIC: [u8; 4] = [0x50, 0x4b, 0x1, 0x2];
 
 /// bit #0 (0x0001 = 1 << 0) of general purpose bit flag
 pub const DATA_ENCRYPTED_FLAG_BIT: u16 = 0x0001;
+/// bit #1 (0x0002 = 1 << 1) of general purpose bit flag (LZMA: the end-of-stream marker is used)
+pub const LZMA_END_OF_STREAM_MARKER_FLAG_BIT: u16 = 0x0002;
 /// bit #3 (0x0008 = 1 << 3) of general purpose bit flag
 pub const DATA_DESCRIPTOR_EXISTS_FLAG_BIT: u16 = 0x0008;
+/// bit #6 (0x0040 = 1 << 6) of general purpose bit flag
+pub const STRONG_ENCRYPTION_FLAG_BIT: u16 = 0x0040;
 /// bit #11 (0x0800 = 1 << 11) of general purpose bit flag
 pub const UTF8_FLAG_BIT: u16 = 0x0800;
 
 /// ZIPファイルのセントラルディレクトリの1エントリー
 /// An entry of central directory of ZIP file
+#[derive(Clone)]
 pub struct ZipCDEntry {
     /// As the name implies; see 4.4.2 in https://pkware.cachefly.net/webdocs/casestudies/APPNOTE.TXT
     ///
@@ -155,7 +165,6 @@ impl ZipCDEntry {
         self.internal_file_attributes = read.read_u16::<LE>()?;
         self.external_file_attributes = read.read_u32::<LE>()?;
         self.local_header_position = read.read_u32::<LE>()?;
-        self.check_unsupported()?;
         let read_file_name_length = read
             .take(self.file_name_length as u64)
             .read_to_end(&mut self.file_name_raw)?;
@@ -189,6 +198,7 @@ impl ZipCDEntry {
                 ),
             });
         }
+        self.zip64_extended_information()?;
         return Ok(());
     }
     /// Sets bit #11 of general purpose bit to indicate that the file name & comment are encoded in UTF-8.
@@ -204,6 +214,24 @@ impl ZipCDEntry {
         self.file_name_length = name.len() as u16;
         self.file_name_raw.clone_from(name);
     }
+    /// Replaces the extra field.
+    ///
+    /// # Arguments
+    ///
+    /// * `extra_field` - Slice of new extra field (e.g. built by `ZipExtraFieldBuilder`)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of `ErrorKind::InvalidInput` if `extra_field` is longer than 65535 bytes.
+    pub fn set_extra_field_from_slice(&mut self, extra_field: &Vec<u8>) -> core2::io::Result<()> {
+        self.extra_field_length = extra_field_length(extra_field)?;
+        self.extra_field.clone_from(extra_field);
+        return Ok(());
+    }
+    /// Returns an iterator over the records (header ID and data) in the extra field
+    pub fn extra_fields(&self) -> ZipExtraFieldIterator<'_> {
+        return ZipExtraFieldIterator::new(&self.extra_field);
+    }
     /// Replaces the file comment
     ///
     /// # Arguments
@@ -221,16 +249,188 @@ impl ZipCDEntry {
     pub fn is_encrypted_data(&self) -> bool {
         return (DATA_ENCRYPTED_FLAG_BIT & self.general_purpose_flags) != 0;
     }
+    /// Returns ZIP64 extended information extra field (0x0001) if exists
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError::InvalidZipArchive` if the extra field is broken.
+    pub fn zip64_extended_information(
+        &self,
+    ) -> Result<Option<Zip64ExtendedInformation>, ZipReadError> {
+        return match find_extra_field(&self.extra_field, ZIP64_EXTRA_FIELD_ID) {
+            Some(data) => Ok(Some(Zip64ExtendedInformation::from_bytes(
+                data,
+                self.uncompressed_size == u32::MAX,
+                self.compressed_size == u32::MAX,
+                self.local_header_position == u32::MAX,
+                self.disk_number_start == u16::MAX,
+            )?)),
+            None => Ok(None),
+        };
+    }
+    /// Returns the compressed size, taking ZIP64 extended information extra field into account
+    pub fn effective_compressed_size(&self) -> u64 {
+        return self
+            .zip64_extended_information()
+            .ok()
+            .flatten()
+            .and_then(|zip64| zip64.compressed_size)
+            .unwrap_or(self.compressed_size as u64);
+    }
+    /// Returns the uncompressed size, taking ZIP64 extended information extra field into account
+    pub fn effective_uncompressed_size(&self) -> u64 {
+        return self
+            .zip64_extended_information()
+            .ok()
+            .flatten()
+            .and_then(|zip64| zip64.uncompressed_size)
+            .unwrap_or(self.uncompressed_size as u64);
+    }
+    /// Returns the **absolute** position of the local header, taking ZIP64 extended information extra field into account
+    pub fn effective_local_header_position(&self) -> u64 {
+        return self
+            .zip64_extended_information()
+            .ok()
+            .flatten()
+            .and_then(|zip64| zip64.local_header_position)
+            .unwrap_or(self.local_header_position as u64);
+    }
+    /// Returns the number of the disk where the file starts, taking ZIP64 extended information extra field into account
+    pub fn effective_disk_number_start(&self) -> u32 {
+        return self
+            .zip64_extended_information()
+            .ok()
+            .flatten()
+            .and_then(|zip64| zip64.disk_number_start)
+            .unwrap_or(self.disk_number_start as u32);
+    }
+    /// Sets the compressed and uncompressed sizes.
+    ///
+    /// Sizes that do not fit in 32 bits are stored in ZIP64 extended information extra field.
+    ///
+    /// # Arguments
+    ///
+    /// * `compressed_size` - compressed size
+    /// * `uncompressed_size` - uncompressed size
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of `ErrorKind::InvalidInput` if the extra field gets longer than 65535 bytes.
+    pub fn set_sizes(&mut self, compressed_size: u64, uncompressed_size: u64) -> core2::io::Result<()> {
+        let local_header_position = self.effective_local_header_position();
+        let disk_number_start = self.effective_disk_number_start();
+        return self.update_zip64_extended_information(
+            compressed_size,
+            uncompressed_size,
+            local_header_position,
+            disk_number_start,
+        );
+    }
+    /// Sets the **absolute** position of the local header.
+    ///
+    /// A position that does not fit in 32 bits is stored in ZIP64 extended information extra field.
+    ///
+    /// # Arguments
+    ///
+    /// * `local_header_position` - position of the signature of the local header
+    ///
+    /// # Errors
+    ///
+    /// See `set_sizes`.
+    pub fn set_local_header_position(&mut self, local_header_position: u64) -> core2::io::Result<()> {
+        let compressed_size = self.effective_compressed_size();
+        let uncompressed_size = self.effective_uncompressed_size();
+        let disk_number_start = self.effective_disk_number_start();
+        return self.update_zip64_extended_information(
+            compressed_size,
+            uncompressed_size,
+            local_header_position,
+            disk_number_start,
+        );
+    }
+    /// Sets the number of the disk where the file starts.
+    ///
+    /// A number that does not fit in 16 bits is stored in ZIP64 extended information extra field.
+    ///
+    /// # Arguments
+    ///
+    /// * `disk_number_start` - number of the disk (0-based)
+    ///
+    /// # Errors
+    ///
+    /// See `set_sizes`.
+    pub fn set_disk_number_start(&mut self, disk_number_start: u32) -> core2::io::Result<()> {
+        let compressed_size = self.effective_compressed_size();
+        let uncompressed_size = self.effective_uncompressed_size();
+        let local_header_position = self.effective_local_header_position();
+        return self.update_zip64_extended_information(
+            compressed_size,
+            uncompressed_size,
+            local_header_position,
+            disk_number_start,
+        );
+    }
+    /// Stores the given values in the 32-bit fields or ZIP64 extended information extra field and keeps `extra_field_length` consistent
+    ///
+    /// Nothing is changed if the extra field gets longer than 65535 bytes.
+    fn update_zip64_extended_information(
+        &mut self,
+        compressed_size: u64,
+        uncompressed_size: u64,
+        local_header_position: u64,
+        disk_number_start: u32,
+    ) -> core2::io::Result<()> {
+        let zip64 = Zip64ExtendedInformation {
+            uncompressed_size: if uncompressed_size >= u32::MAX as u64 {
+                Some(uncompressed_size)
+            } else {
+                None
+            },
+            compressed_size: if compressed_size >= u32::MAX as u64 {
+                Some(compressed_size)
+            } else {
+                None
+            },
+            local_header_position: if local_header_position >= u32::MAX as u64 {
+                Some(local_header_position)
+            } else {
+                None
+            },
+            disk_number_start: if disk_number_start >= u16::MAX as u32 {
+                Some(disk_number_start)
+            } else {
+                None
+            },
+        };
+        if zip64.is_empty() {
+            self.extra_field_length =
+                replace_extra_field(&mut self.extra_field, ZIP64_EXTRA_FIELD_ID, None)?;
+        } else {
+            self.extra_field_length = replace_extra_field(
+                &mut self.extra_field,
+                ZIP64_EXTRA_FIELD_ID,
+                Some(&zip64.to_bytes()),
+            )?;
+            if self.version_required_to_extract < ZIP64_VERSION_REQUIRED_TO_EXTRACT {
+                self.version_required_to_extract = ZIP64_VERSION_REQUIRED_TO_EXTRACT;
+            }
+        }
+        self.uncompressed_size = core::cmp::min(uncompressed_size, u32::MAX as u64) as u32;
+        self.compressed_size = core::cmp::min(compressed_size, u32::MAX as u64) as u32;
+        self.local_header_position = core::cmp::min(local_header_position, u32::MAX as u64) as u32;
+        self.disk_number_start = core::cmp::min(disk_number_start, u16::MAX as u32) as u16;
+        return Ok(());
+    }
     /// Returns `Error` if the file and central directory have unsupported features
     pub fn check_unsupported(&self) -> Result<(), ZipReadError> {
-        if self.disk_number_start != 0 {
+        if self.effective_disk_number_start() != 0 {
             return Err(ZipReadError::UnsupportedZipArchive {
                 reason: "it is one of splitted arvhives".to_string(),
             });
         }
-        if self.is_encrypted_data() {
+        if (STRONG_ENCRYPTION_FLAG_BIT & self.general_purpose_flags) != 0 {
             return Err(ZipReadError::UnsupportedZipArchive {
-                reason: "encrypted data is not supported".to_string(),
+                reason: "strong encryption is not supported".to_string(),
             });
         }
         return Ok(());
@@ -273,6 +473,14 @@ impl ZipCDEntry {
     /// * `read` - file handler (must be at the head of the signature)
     pub fn read_and_generate_from_signature<T: ReadBytesExt + core2::io::Seek>(
         read: &mut T,
+    ) -> Result<Self, ZipReadError> {
+        let result = Self::read_and_generate_from_signature_unchecked(read)?;
+        result.check_unsupported()?;
+        return Ok(result);
+    }
+    /// Same as `read_and_generate_from_signature`, but `check_unsupported` is not called
+    fn read_and_generate_from_signature_unchecked<T: ReadBytesExt + core2::io::Seek>(
+        read: &mut T,
     ) -> Result<Self, ZipReadError> {
         let mut signature_candidate: [u8; 4] = [0; 4];
         let start_pos = read.seek(SeekFrom::Current(0))?;
@@ -296,18 +504,30 @@ impl ZipCDEntry {
     /// * `read` - file handler
     /// * `eocd` - EOCD object
     pub fn all_from_eocd<T: ReadBytesExt + core2::io::Seek>(
+        read: &mut T,
+        eocd: &ZipEOCD,
+    ) -> Result<Vec<Self>, ZipReadError> {
+        let result = Self::all_from_eocd_unchecked(read, eocd)?;
+        for cd in &result {
+            cd.check_unsupported()?;
+        }
+        return Ok(result);
+    }
+    /// Same as `all_from_eocd`, but `check_unsupported` is not called (e.g. entries on other disks are accepted)
+    pub(crate) fn all_from_eocd_unchecked<T: ReadBytesExt + core2::io::Seek>(
         mut read: &mut T,
         eocd: &ZipEOCD,
     ) -> Result<Vec<Self>, ZipReadError> {
-        read.seek(SeekFrom::Start(eocd.cd_starting_position as u64))?;
+        read.seek(SeekFrom::Start(eocd.effective_cd_starting_position()))?;
         let mut result: Vec<Self> = vec![];
-        for _ in 0..eocd.n_cd_entries {
-            result.push(Self::read_and_generate_from_signature(&mut read)?);
+        for _ in 0..eocd.effective_n_cd_entries() {
+            result.push(Self::read_and_generate_from_signature_unchecked(&mut read)?);
         }
         let end_pos = read.seek(SeekFrom::Current(0))?;
-        if end_pos != eocd.starting_position_with_signature {
+        let cd_ending_position = eocd.cd_ending_position();
+        if end_pos != cd_ending_position {
             return Err(ZipReadError::UnsupportedZipArchive {
-                reason: format!("there are extra data ({}) between central directory and end of central directory", eocd.starting_position_with_signature - end_pos)
+                reason: format!("there are extra data ({}) between central directory and end of central directory", cd_ending_position - end_pos)
             });
         }
         return Ok(result);
@@ -321,11 +541,16 @@ impl ZipCDEntry {
     ///
     /// * `file_header` - local file header structure
     /// * `signature_position` - the position of the signature of **central directory** (not local file header)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of `ErrorKind::InvalidInput` if the extra field gets longer than 65535 bytes
+    /// with ZIP64 extended information extra field.
     pub fn from_local_file_header(
         file_header: &ZipLocalFileHeader,
         signature_position: u64,
-    ) -> Self {
-        return Self {
+    ) -> core2::io::Result<Self> {
+        let mut result = Self {
             compressed_size: file_header.compressed_size,
             compression_method: file_header.compression_method,
             crc32: file_header.crc32,
@@ -345,16 +570,260 @@ impl ZipCDEntry {
             internal_file_attributes: 0, // assuming the file is binary
             last_mod_date: file_header.last_mod_date,
             last_mod_time: file_header.last_mod_time,
-            local_header_position: file_header.starting_position_with_signature as u32,
+            local_header_position: 0,
             starting_position_with_signature: signature_position,
             starting_position_without_signature: signature_position + CD_MAGIC.len() as u64,
             uncompressed_size: file_header.uncompressed_size,
             version_made_by: 0, // MS-DOS compatible
             version_required_to_extract: file_header.version_required_to_extract,
         };
+        // ZIP64 extended information extra field has a different layout from the local file header
+        result.update_zip64_extended_information(
+            file_header.effective_compressed_size(),
+            file_header.effective_uncompressed_size(),
+            file_header.starting_position_with_signature,
+            0,
+        )?;
+        return Ok(result);
     }
 }
 
 fn is_directory(path: &[u8]) -> bool {
     return path.last() == Some(&b'/');
 }
+
+/// バイト列からファイル名・拡張フィールド・コメントを借用するセントラルディレクトリの1エントリー /
+/// An entry of central directory borrowing the file name, extra field and comment from a byte sequence
+///
+/// Generated by `from_bytes` without copying; see `ZipCDEntry` for the meaning of the fields.
+/// Unlike `ZipCDEntry`, unsupported features (e.g. encryption) are not rejected while parsing.
+pub struct ZipCDEntryRef<'a> {
+    pub version_made_by: u16,
+    pub version_required_to_extract: u16,
+    pub general_purpose_flags: u16,
+    pub compression_method: u16,
+    pub last_mod_time: u16,
+    pub last_mod_date: u16,
+    pub crc32: u32,
+    pub compressed_size: u32,
+    pub uncompressed_size: u32,
+    pub file_name_length: u16,
+    pub extra_field_length: u16,
+    pub file_comment_length: u16,
+    pub disk_number_start: u16,
+    pub internal_file_attributes: u16,
+    pub external_file_attributes: u32,
+    pub local_header_position: u32,
+    /// Byte sequence of the file name (borrowed)
+    pub file_name_raw: &'a [u8],
+    /// Byte sequence of extra field (borrowed)
+    pub extra_field: &'a [u8],
+    /// File comment (borrowed)
+    pub file_comment: &'a [u8],
+    pub starting_position_with_signature: u64,
+    pub starting_position_without_signature: u64,
+}
+
+impl<'a> ZipCDEntryRef<'a> {
+    /// Reads a central directory entry at the start of `bytes`
+    ///
+    /// The starting positions are relative to `bytes`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - byte sequence starting with the signature
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, ZipReadError> {
+        return Self::from_bytes_at(bytes, 0);
+    }
+
+    /// Reads a central directory entry at `position` of a whole ZIP archive on memory
+    ///
+    /// # Arguments
+    ///
+    /// * `archive` - byte sequence of the whole ZIP archive
+    /// * `position` - **absolute** position of the signature
+    pub fn from_bytes_at(archive: &'a [u8], position: u64) -> Result<Self, ZipReadError> {
+        let mut read = slice_from(archive, position).unwrap_or(&[]);
+        if take_slice(&mut read, CD_MAGIC.len() as u64) != CD_MAGIC {
+            return Err(ZipReadError::InvalidZipArchive {
+                reason: format!(
+                    "assumed central directry signature doesn't appear at position {}",
+                    position
+                ),
+            });
+        }
+        let mut result = Self {
+            version_made_by: read.read_u16::<LE>()?,
+            version_required_to_extract: read.read_u16::<LE>()?,
+            general_purpose_flags: read.read_u16::<LE>()?,
+            compression_method: read.read_u16::<LE>()?,
+            last_mod_time: read.read_u16::<LE>()?,
+            last_mod_date: read.read_u16::<LE>()?,
+            crc32: read.read_u32::<LE>()?,
+            compressed_size: read.read_u32::<LE>()?,
+            uncompressed_size: read.read_u32::<LE>()?,
+            file_name_length: read.read_u16::<LE>()?,
+            extra_field_length: read.read_u16::<LE>()?,
+            file_comment_length: read.read_u16::<LE>()?,
+            disk_number_start: read.read_u16::<LE>()?,
+            internal_file_attributes: read.read_u16::<LE>()?,
+            external_file_attributes: read.read_u32::<LE>()?,
+            local_header_position: read.read_u32::<LE>()?,
+            file_name_raw: &[],
+            extra_field: &[],
+            file_comment: &[],
+            starting_position_with_signature: position,
+            starting_position_without_signature: position + CD_MAGIC.len() as u64,
+        };
+        result.file_name_raw = take_slice(&mut read, result.file_name_length as u64);
+        if result.file_name_raw.len() != result.file_name_length as usize {
+            return Err(ZipReadError::InvalidZipArchive {
+                reason: format!(
+                    "file name length is invalid (expected from length value field: {} / got: {})",
+                    result.file_name_length,
+                    result.file_name_raw.len()
+                ),
+            });
+        }
+        result.extra_field = take_slice(&mut read, result.extra_field_length as u64);
+        if result.extra_field.len() != result.extra_field_length as usize {
+            return Err(ZipReadError::InvalidZipArchive {
+                reason: format!(
+                    "extra field length is invalid (expected from length value field: {} / got {}",
+                    result.extra_field_length,
+                    result.extra_field.len()
+                ),
+            });
+        }
+        result.file_comment = take_slice(&mut read, result.file_comment_length as u64);
+        if result.file_comment.len() != result.file_comment_length as usize {
+            return Err(ZipReadError::InvalidZipArchive {
+                reason: format!(
+                    "file comment length is invalid (expected from length value field: {} / got {}",
+                    result.file_comment_length,
+                    result.file_comment.len()
+                ),
+            });
+        }
+        result.zip64_extended_information()?;
+        return Ok(result);
+    }
+
+    /// Reads all the central directory entries of a whole ZIP archive on memory
+    ///
+    /// # Arguments
+    ///
+    /// * `archive` - byte sequence of the whole ZIP archive
+    /// * `eocd` - EOCD read from `archive`
+    pub fn all_from_eocd(archive: &'a [u8], eocd: &ZipEOCDRef) -> Result<Vec<Self>, ZipReadError> {
+        let mut pos = eocd.effective_cd_starting_position();
+        let mut result: Vec<Self> = vec![];
+        for _ in 0..eocd.effective_n_cd_entries() {
+            let entry = Self::from_bytes_at(archive, pos)?;
+            pos = entry.starting_position_with_signature + entry.size();
+            result.push(entry);
+        }
+        let cd_ending_position = eocd.cd_ending_position();
+        if pos != cd_ending_position {
+            return Err(ZipReadError::UnsupportedZipArchive {
+                reason: format!("there are extra data ({}) between central directory and end of central directory", cd_ending_position.wrapping_sub(pos))
+            });
+        }
+        return Ok(result);
+    }
+
+    /// Returns the size of this entry including the signature
+    fn size(&self) -> u64 {
+        return 46
+            + self.file_name_length as u64
+            + self.extra_field_length as u64
+            + self.file_comment_length as u64;
+    }
+
+    /// Returns `true` if the file name & comment are encoded in UTF-8
+    pub fn is_encoded_in_utf8(&self) -> bool {
+        return (UTF8_FLAG_BIT & self.general_purpose_flags) != 0;
+    }
+
+    /// Returns an iterator over the records (header ID and data) in the extra field
+    pub fn extra_fields(&self) -> ZipExtraFieldIterator<'a> {
+        return ZipExtraFieldIterator::new(self.extra_field);
+    }
+
+    /// Returns ZIP64 extended information extra field (0x0001) if exists
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError::InvalidZipArchive` if the extra field is broken.
+    pub fn zip64_extended_information(
+        &self,
+    ) -> Result<Option<Zip64ExtendedInformation>, ZipReadError> {
+        return match find_extra_field(self.extra_field, ZIP64_EXTRA_FIELD_ID) {
+            Some(data) => Ok(Some(Zip64ExtendedInformation::from_bytes(
+                data,
+                self.uncompressed_size == u32::MAX,
+                self.compressed_size == u32::MAX,
+                self.local_header_position == u32::MAX,
+                self.disk_number_start == u16::MAX,
+            )?)),
+            None => Ok(None),
+        };
+    }
+
+    /// Returns the compressed size, taking ZIP64 extended information extra field into account
+    pub fn effective_compressed_size(&self) -> u64 {
+        return self
+            .zip64_extended_information()
+            .ok()
+            .flatten()
+            .and_then(|zip64| zip64.compressed_size)
+            .unwrap_or(self.compressed_size as u64);
+    }
+
+    /// Returns the uncompressed size, taking ZIP64 extended information extra field into account
+    pub fn effective_uncompressed_size(&self) -> u64 {
+        return self
+            .zip64_extended_information()
+            .ok()
+            .flatten()
+            .and_then(|zip64| zip64.uncompressed_size)
+            .unwrap_or(self.uncompressed_size as u64);
+    }
+
+    /// Returns the **absolute** position of the local header, taking ZIP64 extended information extra field into account
+    pub fn effective_local_header_position(&self) -> u64 {
+        return self
+            .zip64_extended_information()
+            .ok()
+            .flatten()
+            .and_then(|zip64| zip64.local_header_position)
+            .unwrap_or(self.local_header_position as u64);
+    }
+
+    /// Converts into `ZipCDEntry` by copying the file name, extra field and comment
+    pub fn into_owned(self) -> ZipCDEntry {
+        return ZipCDEntry {
+            version_made_by: self.version_made_by,
+            version_required_to_extract: self.version_required_to_extract,
+            general_purpose_flags: self.general_purpose_flags,
+            compression_method: self.compression_method,
+            last_mod_time: self.last_mod_time,
+            last_mod_date: self.last_mod_date,
+            crc32: self.crc32,
+            compressed_size: self.compressed_size,
+            uncompressed_size: self.uncompressed_size,
+            file_name_length: self.file_name_length,
+            extra_field_length: self.extra_field_length,
+            file_comment_length: self.file_comment_length,
+            disk_number_start: self.disk_number_start,
+            internal_file_attributes: self.internal_file_attributes,
+            external_file_attributes: self.external_file_attributes,
+            local_header_position: self.local_header_position,
+            file_name_raw: self.file_name_raw.to_vec(),
+            extra_field: self.extra_field.to_vec(),
+            file_comment: self.file_comment.to_vec(),
+            starting_position_with_signature: self.starting_position_with_signature,
+            starting_position_without_signature: self.starting_position_without_signature,
+        };
+    }
+}
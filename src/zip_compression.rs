@@ -0,0 +1,1191 @@
+#[cfg(feature = "lzma")]
+use super::zip_central_directory::LZMA_END_OF_STREAM_MARKER_FLAG_BIT;
+use super::zip_crc32::Crc32;
+use super::zip_crypto::AUTHENTICATION_CODE_MISMATCH_MESSAGE;
+use super::zip_error::ZipReadError;
+
+use compression::prelude::{CompressionError, Decoder, Deflater};
+#[cfg(feature = "bzip2")]
+use compression::prelude::{Action, BZip2Decoder, BZip2Encoder, Encoder};
+use core2::io::{BufRead, Error, ErrorKind, Read, Write};
+#[cfg(feature = "lzma")]
+use lzma_rust2::Write as _;
+#[cfg(feature = "lzma")]
+use lzma_rust2::{
+    LzmaOptions, LzmaStream, LzmaWriter, Status, StreamResult, XzOptions, XzStream, XzWriter,
+};
+#[cfg(feature = "deflate-miniz")]
+use miniz_oxide::deflate::core::{create_comp_flags_from_zip_params, CompressorOxide};
+#[cfg(feature = "deflate-miniz")]
+use miniz_oxide::{MZError, MZFlush, MZStatus};
+#[cfg(feature = "zstd")]
+use ruzstd::decoding::{BlockDecodingStrategy, FrameDecoder};
+#[cfg(feature = "zstd")]
+use ruzstd::encoding::CompressionLevel;
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use alloc::format;
+
+/// Compression method: stored (no compression)
+pub const COMPRESSION_METHOD_STORED: u16 = 0;
+/// Compression method: deflated
+pub const COMPRESSION_METHOD_DEFLATE: u16 = 8;
+/// Compression method: bzip2
+pub const COMPRESSION_METHOD_BZIP2: u16 = 12;
+/// Compression method: LZMA
+pub const COMPRESSION_METHOD_LZMA: u16 = 14;
+/// Compression method: Zstandard
+pub const COMPRESSION_METHOD_ZSTD: u16 = 93;
+/// Compression method: XZ
+pub const COMPRESSION_METHOD_XZ: u16 = 95;
+
+/// Default compression level (0: no compression - 9: best compression)
+pub const DEFAULT_COMPRESSION_LEVEL: u8 = 6;
+/// Maximum compression level
+pub const MAX_COMPRESSION_LEVEL: u8 = 9;
+/// Default compression level of Zstandard (the default of the reference implementation)
+pub const DEFAULT_ZSTD_COMPRESSION_LEVEL: u8 = 3;
+/// Maximum compression level of Zstandard (levels 1 - 22 all use the fastest level of `ruzstd` crate)
+pub const MAX_ZSTD_COMPRESSION_LEVEL: u8 = 22;
+
+/// Size of the buffer to read compressed data
+const INPUT_BUFFER_SIZE: usize = 4096;
+/// Maximum length of a stored (non-compressed) deflate block
+const MAX_STORED_BLOCK_SIZE: usize = u16::MAX as usize;
+/// Upper limit of the capacity reserved in advance from an untrusted size
+const MAX_PREALLOCATED_SIZE: usize = 1 << 20;
+/// End-of-stream marker of bzip2 (the square root of pi), followed by the combined CRC
+#[cfg(feature = "bzip2")]
+const BZIP2_END_OF_STREAM_MAGIC: u128 = 0x1772_4538_5090;
+/// Mask of the bits of `BZIP2_END_OF_STREAM_MAGIC`
+#[cfg(feature = "bzip2")]
+const BZIP2_END_OF_STREAM_MAGIC_MASK: u128 = (1 << 48) - 1;
+/// Number of bits of the end-of-stream marker and the combined CRC of bzip2
+#[cfg(feature = "bzip2")]
+const BZIP2_END_OF_STREAM_BITS: u64 = 48 + 32;
+/// Size of the LZMA properties (the properties byte and the dictionary size)
+#[cfg(feature = "lzma")]
+const LZMA_PROPERTIES_SIZE: u16 = 5;
+/// Version of LZMA SDK written in the LZMA header (ignored by extractors)
+#[cfg(feature = "lzma")]
+const LZMA_SDK_VERSION: [u8; 2] = [9, 20];
+/// Size of the uncompressed data compressed into a zstd frame by `ZipCompressWriter`
+#[cfg(feature = "zstd")]
+const ZSTD_FRAME_SIZE: usize = 1 << 20;
+
+/// Iterator over bytes of a `Read` object, fed to decoders of `compression` crate
+///
+/// An I/O error ends the iteration (or only the padding follows) and is kept in `error`.
+struct ReadBytes<R: Read> {
+    read: R,
+    buf: Vec<u8>,
+    pos: usize,
+    len: usize,
+    error: Option<Error>,
+    /// `Some` to yield zeros forever after the end of `read`, or `None` to end the iteration there
+    padding: Option<Padding>,
+    /// `true` after the end of the compressed stream
+    finished: bool,
+}
+
+/// State of the zeros yielded by `ReadBytes::with_padding` after the end of `read`
+struct Padding {
+    /// last bytes yielded from `read` (the last one in the lowest bits)
+    last_bytes: u128,
+    /// number of bytes yielded from `read`
+    n_bytes: u64,
+    /// `true` after a zero has been yielded beyond the end of `read`
+    is_beyond_end: bool,
+}
+
+impl<R: Read> ReadBytes<R> {
+    fn new(read: R) -> Self {
+        return Self {
+            read,
+            buf: vec![0; INPUT_BUFFER_SIZE],
+            pos: 0,
+            len: 0,
+            error: None,
+            padding: None,
+            finished: false,
+        };
+    }
+
+    /// Generates an iterator that yields zeros forever after the end of `read`
+    ///
+    /// The bzip2 decoder of `compression` crate 0.1.5 reads one more byte after the end of a stream
+    /// to look for another stream, and its bit reader is broken (undefined behavior in `CircularBuffer::append`)
+    /// when the input is exhausted.  Padding keeps the input from running out;
+    /// whether the data before it ends with a stream is examined by `is_end_of_bzip2_stream`.
+    #[cfg(feature = "bzip2")]
+    fn with_padding(read: R) -> Self {
+        let mut result = Self::new(read);
+        result.padding = Some(Padding {
+            last_bytes: 0,
+            n_bytes: 0,
+            is_beyond_end: false,
+        });
+        return result;
+    }
+
+    /// Returns `true` if a byte beyond the end of `read` has been requested
+    #[cfg(feature = "bzip2")]
+    fn is_beyond_end(&self) -> bool {
+        return matches!(&self.padding, Some(padding) if padding.is_beyond_end);
+    }
+
+    /// Returns `true` if `read` ends with the end-of-stream marker and the combined CRC of a bzip2 stream
+    ///
+    /// The marker and the CRC (80 bits) are followed by at most 7 bits of zero padding to the byte boundary.
+    /// The decoder reads beyond `read` only after the combined CRC matches if `read` ends there.
+    #[cfg(feature = "bzip2")]
+    fn is_end_of_bzip2_stream(&self) -> bool {
+        let padding = match &self.padding {
+            Some(padding) => padding,
+            None => return false,
+        };
+        return (0..8).any(|n_padding_bits| {
+            padding.n_bytes * 8 >= BZIP2_END_OF_STREAM_BITS + n_padding_bits
+                && padding.last_bytes & ((1 << n_padding_bits) - 1) == 0
+                && (padding.last_bytes >> (n_padding_bits + 32)) & BZIP2_END_OF_STREAM_MAGIC_MASK
+                    == BZIP2_END_OF_STREAM_MAGIC
+        });
+    }
+
+    /// Returns the buffered bytes, reading from `read` if all of them have been consumed
+    ///
+    /// Empty at the end of `read` or after an I/O error (kept in `error`).
+    #[cfg(any(feature = "lzma", feature = "zstd"))]
+    fn fill_buf(&mut self) -> &[u8] {
+        if self.pos >= self.len && self.error.is_none() {
+            match self.read.read(&mut self.buf) {
+                Ok(len) => {
+                    self.pos = 0;
+                    self.len = len;
+                }
+                Err(e) => self.error = Some(e),
+            }
+        }
+        return &self.buf[self.pos..self.len];
+    }
+
+    /// Marks `n` bytes returned by `fill_buf` as consumed
+    #[cfg(any(feature = "lzma", feature = "zstd"))]
+    fn consume(&mut self, n: usize) {
+        self.pos += n;
+    }
+
+    /// Reads and discards the rest of `read` until its end, and marks the end of the compressed stream
+    ///
+    /// Called at the end of the compressed stream, so that a `Read` adapter in front of the decoder
+    /// (e.g. `ZipDecryptReader` verifying an authentication code) sees the end of its data.
+    fn skip_to_end(&mut self) {
+        self.padding = None;
+        while self.next().is_some() {}
+        self.finished = true;
+    }
+
+    /// Returns the next byte of padding (zero), or `None` if `read` is not padded
+    fn next_padding(&mut self) -> Option<u8> {
+        let padding = self.padding.as_mut()?;
+        padding.is_beyond_end = true;
+        return Some(0);
+    }
+}
+
+impl<R: Read> Iterator for ReadBytes<R> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.pos >= self.len {
+            if self.error.is_some() {
+                return self.next_padding();
+            }
+            match self.read.read(&mut self.buf) {
+                Ok(0) => return self.next_padding(),
+                Ok(len) => {
+                    self.pos = 0;
+                    self.len = len;
+                }
+                Err(e) => {
+                    self.error = Some(e);
+                    return self.next_padding();
+                }
+            }
+        }
+        let byte = self.buf[self.pos];
+        self.pos += 1;
+        if let Some(padding) = &mut self.padding {
+            padding.last_bytes = (padding.last_bytes << 8) | byte as u128;
+            padding.n_bytes += 1;
+        }
+        return Some(byte);
+    }
+}
+
+#[cfg(feature = "zstd")]
+impl<R: Read> ruzstd::io::Read for ReadBytes<R> {
+    /// Reads buffered bytes for the zstd decoder; an I/O error is kept in `error`
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, ruzstd::io::Error> {
+        let available = self.fill_buf();
+        let n_read = core::cmp::min(available.len(), buf.len());
+        buf[..n_read].copy_from_slice(&available[..n_read]);
+        self.consume(n_read);
+        if n_read == 0 && self.error.is_some() {
+            return Err(ruzstd::io::Error::from(ruzstd::io::ErrorKind::Other));
+        }
+        return Ok(n_read);
+    }
+}
+
+/// Iterator over bytes of a `BufRead` object that records them
+///
+/// Used to find the end of a deflate stream without consuming the data after it:
+/// blocks of the buffer of `read` are copied, and consumed only as far as the decoder has taken them.
+struct RecordingBytes<'r, R: BufRead> {
+    read: &'r mut R,
+    /// bytes of the buffer of `read` not consumed yet
+    block: Vec<u8>,
+    /// number of bytes of `block` taken by the decoder
+    pos: usize,
+    /// bytes taken by the decoder before `block`
+    consumed: Vec<u8>,
+    error: Option<Error>,
+}
+
+impl<R: BufRead> RecordingBytes<'_, R> {
+    /// Consumes the bytes taken by the decoder from `read` and returns all of them
+    fn finish(mut self) -> Vec<u8> {
+        self.read.consume(self.pos);
+        self.consumed.extend_from_slice(&self.block[..self.pos]);
+        return self.consumed;
+    }
+}
+
+impl<R: BufRead> Iterator for RecordingBytes<'_, R> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.pos >= self.block.len() {
+            if self.error.is_some() {
+                return None;
+            }
+            self.read.consume(self.block.len());
+            self.consumed.extend_from_slice(&self.block);
+            self.block.clear();
+            self.pos = 0;
+            match self.read.fill_buf() {
+                Ok([]) => {
+                    self.error = Some(Error::from(ErrorKind::UnexpectedEof));
+                    return None;
+                }
+                Ok(available) => self.block.extend_from_slice(available),
+                Err(e) => {
+                    self.error = Some(e);
+                    return None;
+                }
+            }
+        }
+        let byte = self.block[self.pos];
+        self.pos += 1;
+        return Some(byte);
+    }
+}
+
+/// Result of `inflate_to_end`
+pub(crate) struct DeflateStream {
+    /// bytes consumed from the `BufRead` object (may exceed the end of the stream by a few bytes)
+    pub consumed: Vec<u8>,
+    /// CRC-32 of the decompressed data
+    pub crc32: u32,
+    /// size of the decompressed data
+    pub uncompressed_size: u64,
+}
+
+/// Decodes a deflate stream until its final block without knowing its length
+///
+/// The decoder may peek a few bytes beyond the end of the stream; they are included in `consumed`
+/// and nothing after them is consumed from `read`.
+///
+/// # Arguments
+///
+/// * `read` - `BufRead` object at the start of the deflate stream
+pub(crate) fn inflate_to_end<R: BufRead>(read: &mut R) -> Result<DeflateStream, ZipReadError> {
+    let mut decoder = Box::new(Deflater::new());
+    let mut input = RecordingBytes {
+        read,
+        block: vec![],
+        pos: 0,
+        consumed: vec![],
+        error: None,
+    };
+    let mut crc32 = Crc32::new();
+    let mut uncompressed_size: u64 = 0;
+    let mut output: Vec<u8> = Vec::with_capacity(INPUT_BUFFER_SIZE);
+    loop {
+        match decoder.as_mut().next(&mut input) {
+            Some(Ok(byte)) => {
+                output.push(byte);
+                if output.len() >= INPUT_BUFFER_SIZE {
+                    crc32.update(&output);
+                    uncompressed_size += output.len() as u64;
+                    output.clear();
+                }
+            }
+            Some(Err(e)) => {
+                if let Some(io_error) = input.error.take() {
+                    return Err(io_error.into());
+                }
+                return Err(decompression_error(&e));
+            }
+            None => break,
+        }
+    }
+    crc32.update(&output);
+    uncompressed_size += output.len() as u64;
+    return Ok(DeflateStream {
+        consumed: input.finish(),
+        crc32: crc32.value(),
+        uncompressed_size,
+    });
+}
+
+/// Returns `true` if `ZipDecompressReader` supports the compression method (with the enabled features)
+pub(crate) fn is_supported_compression_method(compression_method: u16) -> bool {
+    return match compression_method {
+        COMPRESSION_METHOD_STORED | COMPRESSION_METHOD_DEFLATE => true,
+        COMPRESSION_METHOD_BZIP2 => cfg!(feature = "bzip2"),
+        COMPRESSION_METHOD_LZMA | COMPRESSION_METHOD_XZ => cfg!(feature = "lzma"),
+        COMPRESSION_METHOD_ZSTD => cfg!(feature = "zstd"),
+        _ => false,
+    };
+}
+
+/// Decoder state of `ZipDecompressReader`
+enum DecompressState<R: Read> {
+    Stored(R),
+    Deflate {
+        decoder: Box<Deflater>,
+        input: ReadBytes<R>,
+    },
+    #[cfg(feature = "bzip2")]
+    BZip2 {
+        decoder: Box<BZip2Decoder>,
+        input: ReadBytes<R>,
+    },
+    #[cfg(feature = "lzma")]
+    Lzma {
+        /// `None` until the LZMA header is read
+        decoder: Option<Box<LzmaStream>>,
+        /// uncompressed size, or `None` if the data ends with the end-of-stream marker
+        uncompressed_size: Option<u64>,
+        input: ReadBytes<R>,
+    },
+    #[cfg(feature = "zstd")]
+    Zstd {
+        decoder: Box<FrameDecoder>,
+        /// `true` after the header of the first frame is read
+        has_frame: bool,
+        input: ReadBytes<R>,
+    },
+    #[cfg(feature = "lzma")]
+    Xz {
+        decoder: Box<XzStream>,
+        input: ReadBytes<R>,
+    },
+}
+
+/// `Read` adapter that decompresses the file content of a ZIP entry
+///
+/// Supported compression methods are stored (0), deflated (8), bzip2 (12), LZMA (14), Zstandard (93) and XZ (95);
+/// bzip2, LZMA and XZ, and Zstandard require `bzip2`, `lzma` and `zstd` features respectively.
+///
+/// If the expected CRC-32 is given by `with_expected_crc32`, it is checked against the decompressed data at the end.
+pub struct ZipDecompressReader<R: Read> {
+    state: DecompressState<R>,
+    /// CRC-32 of the decompressed data so far
+    crc32: Crc32,
+    /// CRC-32 recorded in the archive
+    expected_crc32: Option<u32>,
+}
+
+impl<R: Read> ZipDecompressReader<R> {
+    /// Generates a decompressing reader
+    ///
+    /// # Arguments
+    ///
+    /// * `read` - `Read` object of the compressed data (must end at the end of the compressed data)
+    /// * `compression_method` - compression method in the local file header or central directory
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError::UnsupportedZipArchive` if the compression method is not supported
+    /// (or its feature is disabled).
+    pub fn new(read: R, compression_method: u16) -> Result<Self, ZipReadError> {
+        let state = match compression_method {
+            COMPRESSION_METHOD_STORED => DecompressState::Stored(read),
+            COMPRESSION_METHOD_DEFLATE => DecompressState::Deflate {
+                decoder: Box::new(Deflater::new()),
+                input: ReadBytes::new(read),
+            },
+            #[cfg(feature = "bzip2")]
+            COMPRESSION_METHOD_BZIP2 => DecompressState::BZip2 {
+                decoder: Box::new(BZip2Decoder::new()),
+                input: ReadBytes::with_padding(read),
+            },
+            #[cfg(feature = "lzma")]
+            COMPRESSION_METHOD_LZMA => DecompressState::Lzma {
+                decoder: None,
+                uncompressed_size: None,
+                input: ReadBytes::new(read),
+            },
+            #[cfg(feature = "zstd")]
+            COMPRESSION_METHOD_ZSTD => DecompressState::Zstd {
+                decoder: Box::new(FrameDecoder::new()),
+                has_frame: false,
+                input: ReadBytes::new(read),
+            },
+            #[cfg(feature = "lzma")]
+            COMPRESSION_METHOD_XZ => DecompressState::Xz {
+                decoder: Box::new(XzStream::new(false)),
+                input: ReadBytes::new(read),
+            },
+            _ => {
+                return Err(ZipReadError::UnsupportedZipArchive {
+                    reason: format!(
+                        "compression method {} is not supported",
+                        compression_method
+                    ),
+                })
+            }
+        };
+        return Ok(Self {
+            state,
+            crc32: Crc32::new(),
+            expected_crc32: None,
+        });
+    }
+
+    /// Enables CRC-32 verification of the decompressed data
+    ///
+    /// # Arguments
+    ///
+    /// * `expected_crc32` - CRC-32 recorded in the central directory, local file header or data descriptor
+    pub fn with_expected_crc32(mut self, expected_crc32: u32) -> Self {
+        self.expected_crc32 = Some(expected_crc32);
+        return self;
+    }
+
+    /// Sets the general purpose bit flag and the uncompressed size of the entry, which determine the end of LZMA data
+    ///
+    /// LZMA data ends with the end-of-stream marker if bit #1 of the flag is set, or at the uncompressed size otherwise.
+    /// Without this, the end-of-stream marker is expected.  Other compression methods ignore them.
+    ///
+    /// # Arguments
+    ///
+    /// * `general_purpose_flags` - general purpose bit flag of the local file header
+    /// * `uncompressed_size` - uncompressed size recorded in the archive
+    #[cfg_attr(not(feature = "lzma"), allow(unused_mut, unused_variables))]
+    pub fn with_entry_info(mut self, general_purpose_flags: u16, uncompressed_size: u64) -> Self {
+        #[cfg(feature = "lzma")]
+        if let DecompressState::Lzma {
+            uncompressed_size: lzma_uncompressed_size,
+            ..
+        } = &mut self.state
+        {
+            *lzma_uncompressed_size = if (LZMA_END_OF_STREAM_MARKER_FLAG_BIT & general_purpose_flags) != 0 {
+                None
+            } else {
+                Some(uncompressed_size)
+            };
+        }
+        return self;
+    }
+
+    /// Returns CRC-32 of the decompressed data read so far
+    pub fn crc32(&self) -> u32 {
+        return self.crc32.value();
+    }
+
+    /// Reads decompressed bytes into `buf` and returns the number of bytes read (0 at the end of data)
+    ///
+    /// Unlike `Read::read`, broken compressed data is reported as `ZipReadError::InvalidZipArchive`
+    /// and CRC-32 mismatch as `ZipReadError::Crc32Mismatch`.
+    ///
+    /// # Arguments
+    ///
+    /// * `buf` - buffer to store decompressed bytes
+    pub fn read_decompressed(&mut self, buf: &mut [u8]) -> Result<usize, ZipReadError> {
+        let n_read = self.read_decompressed_unchecked(buf)?;
+        self.crc32.update(&buf[..n_read]);
+        if n_read == 0 && !buf.is_empty() {
+            if let Some(expected) = self.expected_crc32 {
+                let actual = self.crc32.value();
+                if actual != expected {
+                    return Err(ZipReadError::Crc32Mismatch { expected, actual });
+                }
+            }
+        }
+        return Ok(n_read);
+    }
+
+    /// `read_decompressed` without CRC-32 verification
+    fn read_decompressed_unchecked(&mut self, buf: &mut [u8]) -> Result<usize, ZipReadError> {
+        return match &mut self.state {
+            DecompressState::Stored(read) => Ok(read.read(buf)?),
+            DecompressState::Deflate { decoder, input } => decode(decoder.as_mut(), input, buf),
+            #[cfg(feature = "bzip2")]
+            DecompressState::BZip2 { decoder, input } => decode(decoder.as_mut(), input, buf),
+            #[cfg(feature = "lzma")]
+            DecompressState::Lzma {
+                decoder,
+                uncompressed_size,
+                input,
+            } => {
+                let decoder = match decoder {
+                    Some(decoder) => decoder,
+                    None => decoder.insert(Box::new(read_lzma_header(input, *uncompressed_size)?)),
+                };
+                decode_stream(input, buf, |input, output, action| {
+                    decoder.process(input, output, action)
+                })
+            }
+            #[cfg(feature = "zstd")]
+            DecompressState::Zstd {
+                decoder,
+                has_frame,
+                input,
+            } => decode_zstd(decoder.as_mut(), has_frame, input, buf),
+            #[cfg(feature = "lzma")]
+            DecompressState::Xz { decoder, input } => {
+                decode_stream(input, buf, |input, output, action| {
+                    decoder.process(input, output, action)
+                })
+            }
+        };
+    }
+
+    /// Reads all the remaining decompressed bytes
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity_hint` - expected size of the result (e.g. uncompressed size)
+    pub fn read_all_decompressed(&mut self, capacity_hint: usize) -> Result<Vec<u8>, ZipReadError> {
+        let mut result: Vec<u8> =
+            Vec::with_capacity(core::cmp::min(capacity_hint, MAX_PREALLOCATED_SIZE));
+        let mut buf: Vec<u8> = vec![0; INPUT_BUFFER_SIZE];
+        loop {
+            let n_read = self.read_decompressed(&mut buf)?;
+            if n_read == 0 {
+                return Ok(result);
+            }
+            result.extend_from_slice(&buf[..n_read]);
+        }
+    }
+}
+
+impl<R: Read> Read for ZipDecompressReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> core2::io::Result<usize> {
+        return self.read_decompressed(buf).map_err(|e| match e {
+            ZipReadError::IOError(io_error) => io_error,
+            ZipReadError::Crc32Mismatch { .. } => {
+                Error::new(ErrorKind::InvalidData, "CRC-32 of the file content mismatches")
+            }
+            ZipReadError::AuthenticationCodeMismatch => {
+                Error::new(ErrorKind::InvalidData, AUTHENTICATION_CODE_MISMATCH_MESSAGE)
+            }
+            _ => Error::new(ErrorKind::InvalidData, "compressed data is broken"),
+        });
+    }
+}
+
+/// Decodes bytes from `input` into `buf` and returns the number of bytes decoded (0 at the end of the stream)
+///
+/// If `input` has padding (bzip2), the stream ends when the decoder reads beyond `input` just after the end of a stream;
+/// reading beyond it elsewhere means that the data is truncated.
+///
+/// # Arguments
+///
+/// * `decoder` - decoder of `compression` crate
+/// * `input` - compressed data
+/// * `buf` - buffer to store decoded bytes
+fn decode<D, R>(
+    decoder: &mut D,
+    input: &mut ReadBytes<R>,
+    buf: &mut [u8],
+) -> Result<usize, ZipReadError>
+where
+    D: Decoder<Input = u8, Output = u8>,
+    D::Error: core::fmt::Display,
+    CompressionError: From<D::Error>,
+    R: Read,
+{
+    let mut n_read: usize = 0;
+    while n_read < buf.len() && !input.finished {
+        let result = decoder.next(input);
+        // the decoder does not stop at an I/O error if `input` has padding
+        if let Some(io_error) = input.error.take() {
+            return Err(io_error.into());
+        }
+        // the result is made from padding, if any
+        #[cfg(feature = "bzip2")]
+        if input.is_beyond_end() {
+            if !input.is_end_of_bzip2_stream() {
+                return Err(ZipReadError::InvalidZipArchive {
+                    reason: "compressed data is truncated".into(),
+                });
+            }
+            input.skip_to_end();
+            break;
+        }
+        match result {
+            Some(Ok(byte)) => {
+                buf[n_read] = byte;
+                n_read += 1;
+            }
+            Some(Err(e)) => return Err(decompression_error(&e)),
+            None => {
+                input.skip_to_end();
+                break;
+            }
+        }
+    }
+    if let Some(io_error) = input.error.take() {
+        return Err(io_error.into());
+    }
+    return Ok(n_read);
+}
+
+/// Decodes bytes from `input` into `buf` with a decoder of `lzma-rust2` crate and returns the number of bytes decoded (0 at the end of the stream)
+///
+/// # Arguments
+///
+/// * `input` - compressed data
+/// * `buf` - buffer to store decoded bytes
+/// * `process` - calls `process` of the decoder with the input, output and action
+#[cfg(feature = "lzma")]
+fn decode_stream<R, F>(input: &mut ReadBytes<R>, buf: &mut [u8], mut process: F) -> Result<usize, ZipReadError>
+where
+    R: Read,
+    F: FnMut(&[u8], &mut [u8], lzma_rust2::Action) -> lzma_rust2::Result<StreamResult>,
+{
+    let mut n_read: usize = 0;
+    while n_read < buf.len() && !input.finished {
+        let available = input.fill_buf();
+        let action = if available.is_empty() {
+            lzma_rust2::Action::Finish
+        } else {
+            lzma_rust2::Action::Run
+        };
+        let result = process(available, &mut buf[n_read..], action);
+        if let Some(io_error) = input.error.take() {
+            return Err(io_error.into());
+        }
+        let result = result.map_err(|e| decompression_error(&format_args!("{:?}", e)))?;
+        input.consume(result.bytes_consumed);
+        n_read += result.bytes_produced;
+        if result.status == Status::StreamEnd {
+            input.skip_to_end();
+            break;
+        }
+        if action == lzma_rust2::Action::Finish && result.bytes_produced == 0 {
+            return Err(ZipReadError::InvalidZipArchive {
+                reason: "compressed data is truncated".into(),
+            });
+        }
+    }
+    if let Some(io_error) = input.error.take() {
+        return Err(io_error.into());
+    }
+    return Ok(n_read);
+}
+
+/// Decodes zstd frames from `input` into `buf` and returns the number of bytes decoded (0 at the end of the data)
+///
+/// Frames are decoded until the end of `input`, and the content checksum of each frame is verified if present.
+///
+/// # Arguments
+///
+/// * `decoder` - frame decoder of `ruzstd` crate
+/// * `has_frame` - `true` after the header of the first frame is read
+/// * `input` - compressed data
+/// * `buf` - buffer to store decoded bytes
+#[cfg(feature = "zstd")]
+fn decode_zstd<R: Read>(
+    decoder: &mut FrameDecoder,
+    has_frame: &mut bool,
+    input: &mut ReadBytes<R>,
+    buf: &mut [u8],
+) -> Result<usize, ZipReadError> {
+    let mut n_read: usize = 0;
+    while n_read < buf.len() && !input.finished {
+        if decoder.can_collect() > 0 {
+            n_read += ruzstd::io::Read::read(decoder, &mut buf[n_read..])
+                .map_err(|e| decompression_error(&e))?;
+            continue;
+        }
+        let result = if decoder.is_finished() {
+            // all the content of the last frame has been read
+            if let (Some(expected), Some(actual)) =
+                (decoder.get_checksum_from_data(), decoder.get_calculated_checksum())
+            {
+                if expected != actual {
+                    return Err(ZipReadError::InvalidZipArchive {
+                        reason: "content checksum of the zstd frame mismatches".into(),
+                    });
+                }
+            }
+            if *has_frame && input.fill_buf().is_empty() && input.error.is_none() {
+                input.skip_to_end();
+                break;
+            }
+            *has_frame = true;
+            decoder.init(&mut *input)
+        } else {
+            decoder
+                .decode_blocks(&mut *input, BlockDecodingStrategy::UptoBlocks(1))
+                .map(|_| ())
+        };
+        if let Some(io_error) = input.error.take() {
+            return Err(io_error.into());
+        }
+        result.map_err(|e| decompression_error(&e))?;
+    }
+    if let Some(io_error) = input.error.take() {
+        return Err(io_error.into());
+    }
+    return Ok(n_read);
+}
+
+/// Reads the LZMA header (see 5.8.8 in APPNOTE) and generates the decoder of the following LZMA data
+///
+/// # Arguments
+///
+/// * `input` - compressed data starting with the LZMA header
+/// * `uncompressed_size` - uncompressed size, or `None` if the data ends with the end-of-stream marker
+#[cfg(feature = "lzma")]
+fn read_lzma_header<R: Read>(
+    input: &mut ReadBytes<R>,
+    uncompressed_size: Option<u64>,
+) -> Result<LzmaStream, ZipReadError> {
+    // LZMA SDK version (2 bytes), properties size (2 bytes), properties byte and dictionary size (4 bytes)
+    let mut header = [0u8; 4 + LZMA_PROPERTIES_SIZE as usize];
+    for byte in header.iter_mut() {
+        *byte = match input.next() {
+            Some(byte) => byte,
+            None => {
+                if let Some(io_error) = input.error.take() {
+                    return Err(io_error.into());
+                }
+                return Err(ZipReadError::InvalidZipArchive {
+                    reason: "LZMA header is truncated".into(),
+                });
+            }
+        };
+    }
+    let properties_size = u16::from_le_bytes([header[2], header[3]]);
+    if properties_size != LZMA_PROPERTIES_SIZE {
+        return Err(ZipReadError::InvalidZipArchive {
+            reason: format!("LZMA properties size is {} (expected: 5)", properties_size),
+        });
+    }
+    let dictionary_size = u32::from_le_bytes([header[5], header[6], header[7], header[8]]);
+    return LzmaStream::new_with_props(
+        uncompressed_size.unwrap_or(u64::MAX),
+        header[4],
+        dictionary_size,
+        None,
+    )
+    .map_err(|e| ZipReadError::InvalidZipArchive {
+        reason: format!("LZMA properties are invalid ({:?})", e),
+    });
+}
+
+/// Converts an error of a decoder into `ZipReadError`
+fn decompression_error<E: core::fmt::Display>(e: &E) -> ZipReadError {
+    return ZipReadError::InvalidZipArchive {
+        reason: format!("compressed data is broken ({})", e),
+    };
+}
+
+/// Encoder state of `ZipCompressWriter`
+enum CompressState {
+    Stored,
+    /// Deflate stream of stored blocks (compression level 0)
+    DeflateStoredBlocks { pending: Vec<u8> },
+    #[cfg(feature = "deflate-miniz")]
+    Deflate { encoder: Box<CompressorOxide> },
+    #[cfg(feature = "bzip2")]
+    BZip2 { encoder: Box<BZip2Encoder> },
+    /// `None` after `finish`; the compressed data is buffered in the `Vec` until written
+    #[cfg(feature = "lzma")]
+    Lzma { encoder: Option<Box<LzmaWriter<Vec<u8>>>> },
+    /// Uncompressed data is compressed into a frame every `ZSTD_FRAME_SIZE` bytes
+    #[cfg(feature = "zstd")]
+    Zstd {
+        level: CompressionLevel,
+        pending: Vec<u8>,
+    },
+    #[cfg(feature = "lzma")]
+    Xz { encoder: Option<Box<XzWriter<Vec<u8>>>> },
+}
+
+/// `Write` adapter that compresses the file content of a ZIP entry
+///
+/// Supported compression methods are stored (0), deflated (8), bzip2 (12), LZMA (14), Zstandard (93) and XZ (95);
+/// deflate (except for level 0), bzip2, LZMA and XZ, and Zstandard require `deflate-miniz`, `bzip2`, `lzma` and `zstd` features respectively.
+/// LZMA data ends with the end-of-stream marker (set bit #1 of general purpose bit flag).
+/// CRC-32 and sizes of the written data are computed on the fly; call `finish` after writing all the data.
+pub struct ZipCompressWriter<W: Write> {
+    write: W,
+    state: CompressState,
+    /// CRC-32 of the uncompressed data so far
+    crc32: Crc32,
+    uncompressed_size: u64,
+    compressed_size: u64,
+    finished: bool,
+}
+
+impl<W: Write> ZipCompressWriter<W> {
+    /// Generates a compressing writer
+    ///
+    /// For deflate, the level is the one of zlib (level 0 emits a deflate stream of stored blocks).
+    /// For bzip2, the level is the block size in 100 kB (level 0 is the same as 1).
+    /// For LZMA and XZ, the level is the preset of XZ Utils.
+    /// For Zstandard, the level is 0 - 22, but the `ruzstd` crate implements only its fastest level (about zstd level 1):
+    /// levels 1 - 22 all compress at that level, and level 0 emits frames of raw (non-compressed) blocks.
+    ///
+    /// # Arguments
+    ///
+    /// * `write` - `Write` object of the compressed data
+    /// * `compression_method` - compression method written in the local file header and central directory
+    /// * `level` - compression level (0 - 9, 0 - 22 for Zstandard); ignored for stored
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of `ErrorKind::InvalidInput` if the compression method (or its feature) or level is not supported.
+    pub fn new(write: W, compression_method: u16, level: u8) -> core2::io::Result<Self> {
+        if compression_method != COMPRESSION_METHOD_ZSTD && level > MAX_COMPRESSION_LEVEL {
+            return Err(Error::new(ErrorKind::InvalidInput, "compression level must be 0 - 9"));
+        }
+        let state = match compression_method {
+            COMPRESSION_METHOD_STORED => CompressState::Stored,
+            COMPRESSION_METHOD_DEFLATE if level == 0 => {
+                CompressState::DeflateStoredBlocks { pending: vec![] }
+            }
+            #[cfg(feature = "deflate-miniz")]
+            COMPRESSION_METHOD_DEFLATE => CompressState::Deflate {
+                // negative window bits: raw deflate stream without zlib header
+                encoder: Box::new(CompressorOxide::new(create_comp_flags_from_zip_params(
+                    level as i32,
+                    -15,
+                    0,
+                ))),
+            },
+            #[cfg(feature = "bzip2")]
+            COMPRESSION_METHOD_BZIP2 => CompressState::BZip2 {
+                encoder: Box::new(BZip2Encoder::new(core::cmp::max(level, 1) as usize)),
+            },
+            #[cfg(feature = "lzma")]
+            COMPRESSION_METHOD_LZMA => {
+                let options = LzmaOptions::with_preset(level as u32);
+                let mut header = LZMA_SDK_VERSION.to_vec();
+                header.extend_from_slice(&LZMA_PROPERTIES_SIZE.to_le_bytes());
+                header.push(options.get_props());
+                header.extend_from_slice(&options.dict_size.to_le_bytes());
+                let encoder = LzmaWriter::new_no_header(header, &options, true).map_err(compression_error)?;
+                CompressState::Lzma {
+                    encoder: Some(Box::new(encoder)),
+                }
+            }
+            #[cfg(feature = "zstd")]
+            COMPRESSION_METHOD_ZSTD if level > MAX_ZSTD_COMPRESSION_LEVEL => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "Zstandard compression level must be 0 - 22",
+                ))
+            }
+            #[cfg(feature = "zstd")]
+            COMPRESSION_METHOD_ZSTD => CompressState::Zstd {
+                level: if level == 0 {
+                    CompressionLevel::Uncompressed
+                } else {
+                    // `ruzstd` implements no level slower than the fastest one
+                    CompressionLevel::Fastest
+                },
+                pending: vec![],
+            },
+            #[cfg(feature = "lzma")]
+            COMPRESSION_METHOD_XZ => CompressState::Xz {
+                encoder: Some(Box::new(
+                    XzWriter::new(Vec::new(), XzOptions::with_preset(level as u32)).map_err(compression_error)?,
+                )),
+            },
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "compression method is not supported",
+                ))
+            }
+        };
+        return Ok(Self {
+            write,
+            state,
+            crc32: Crc32::new(),
+            uncompressed_size: 0,
+            compressed_size: 0,
+            finished: false,
+        });
+    }
+
+    /// Returns CRC-32 of the uncompressed data written so far
+    pub fn crc32(&self) -> u32 {
+        return self.crc32.value();
+    }
+
+    /// Returns the size of the uncompressed data written so far
+    pub fn uncompressed_size(&self) -> u64 {
+        return self.uncompressed_size;
+    }
+
+    /// Returns the size of the compressed data output so far (final after `finish`)
+    pub fn compressed_size(&self) -> u64 {
+        return self.compressed_size;
+    }
+
+    /// Flushes the rest of the compressed data
+    ///
+    /// Nothing can be written after this.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if compressing or writing fails.
+    pub fn finish(&mut self) -> core2::io::Result<()> {
+        if self.finished {
+            return Ok(());
+        }
+        self.finished = true;
+        match &mut self.state {
+            CompressState::Stored => {}
+            CompressState::DeflateStoredBlocks { pending } => {
+                let last_block = core::mem::take(pending);
+                self.compressed_size += write_stored_block(&mut self.write, &last_block, true)?;
+            }
+            #[cfg(feature = "deflate-miniz")]
+            CompressState::Deflate { encoder } => {
+                self.compressed_size += deflate(encoder.as_mut(), &mut self.write, &[], MZFlush::Finish)?;
+            }
+            #[cfg(feature = "bzip2")]
+            CompressState::BZip2 { encoder } => {
+                self.compressed_size += encode(
+                    encoder.as_mut(),
+                    &mut self.write,
+                    &mut core::iter::empty(),
+                    Action::Finish,
+                )?;
+            }
+            #[cfg(feature = "lzma")]
+            CompressState::Lzma { encoder } => {
+                if let Some(encoder) = encoder.take() {
+                    let mut output = encoder.finish().map_err(compression_error)?;
+                    self.compressed_size += write_output(&mut self.write, &mut output)?;
+                }
+            }
+            #[cfg(feature = "zstd")]
+            CompressState::Zstd { level, pending } => {
+                // empty data is still compressed into a frame
+                if !pending.is_empty() || self.compressed_size == 0 {
+                    let last_frame = core::mem::take(pending);
+                    self.compressed_size += write_zstd_frame(&mut self.write, &last_frame, *level)?;
+                }
+            }
+            #[cfg(feature = "lzma")]
+            CompressState::Xz { encoder } => {
+                if let Some(encoder) = encoder.take() {
+                    let mut output = encoder.finish().map_err(compression_error)?;
+                    self.compressed_size += write_output(&mut self.write, &mut output)?;
+                }
+            }
+        }
+        return self.write.flush();
+    }
+
+    /// Returns a mutable reference to the `Write` object of the compressed data
+    pub fn get_mut(&mut self) -> &mut W {
+        return &mut self.write;
+    }
+
+    /// Returns the `Write` object of the compressed data
+    pub fn into_inner(self) -> W {
+        return self.write;
+    }
+}
+
+impl<W: Write> Write for ZipCompressWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> core2::io::Result<usize> {
+        if self.finished {
+            return Err(Error::new(ErrorKind::Other, "compression has already finished"));
+        }
+        match &mut self.state {
+            CompressState::Stored => {
+                self.write.write_all(buf)?;
+                self.compressed_size += buf.len() as u64;
+            }
+            CompressState::DeflateStoredBlocks { pending } => {
+                pending.extend_from_slice(buf);
+                while pending.len() > MAX_STORED_BLOCK_SIZE {
+                    let rest = pending.split_off(MAX_STORED_BLOCK_SIZE);
+                    let block = core::mem::replace(pending, rest);
+                    self.compressed_size += write_stored_block(&mut self.write, &block, false)?;
+                }
+            }
+            #[cfg(feature = "deflate-miniz")]
+            CompressState::Deflate { encoder } => {
+                self.compressed_size += deflate(encoder.as_mut(), &mut self.write, buf, MZFlush::None)?;
+            }
+            #[cfg(feature = "bzip2")]
+            CompressState::BZip2 { encoder } => {
+                self.compressed_size +=
+                    encode(encoder.as_mut(), &mut self.write, &mut buf.iter().cloned(), Action::Run)?;
+            }
+            #[cfg(feature = "lzma")]
+            CompressState::Lzma { encoder: Some(encoder) } => {
+                encoder.write_all(buf).map_err(compression_error)?;
+                self.compressed_size += write_output(&mut self.write, encoder.inner_mut())?;
+            }
+            #[cfg(feature = "zstd")]
+            CompressState::Zstd { level, pending } => {
+                pending.extend_from_slice(buf);
+                while pending.len() >= ZSTD_FRAME_SIZE {
+                    let rest = pending.split_off(ZSTD_FRAME_SIZE);
+                    let frame = core::mem::replace(pending, rest);
+                    self.compressed_size += write_zstd_frame(&mut self.write, &frame, *level)?;
+                }
+            }
+            #[cfg(feature = "lzma")]
+            CompressState::Xz { encoder: Some(encoder) } => {
+                encoder.write_all(buf).map_err(compression_error)?;
+                self.compressed_size += write_output(&mut self.write, encoder.inner_mut())?;
+            }
+            #[cfg(feature = "lzma")]
+            CompressState::Lzma { encoder: None } | CompressState::Xz { encoder: None } => {}
+        }
+        self.crc32.update(buf);
+        self.uncompressed_size += buf.len() as u64;
+        return Ok(buf.len());
+    }
+
+    fn flush(&mut self) -> core2::io::Result<()> {
+        return self.write.flush();
+    }
+}
+
+/// Compresses `data` and returns the compressed data
+///
+/// # Arguments
+///
+/// * `data` - uncompressed data
+/// * `compression_method` - compression method
+/// * `level` - compression level (0 - 9); see `ZipCompressWriter::new`
+///
+/// # Errors
+///
+/// Returns an error of `ErrorKind::InvalidInput` if the compression method or level is not supported.
+pub fn compress(data: &[u8], compression_method: u16, level: u8) -> core2::io::Result<Vec<u8>> {
+    let mut compressor = ZipCompressWriter::new(Vec::new(), compression_method, level)?;
+    compressor.write_all(data)?;
+    compressor.finish()?;
+    return Ok(compressor.into_inner());
+}
+
+/// Feeds `input` to an encoder, writes its output and returns the number of bytes written
+#[cfg(feature = "bzip2")]
+fn encode<E, W, I>(encoder: &mut E, write: &mut W, input: &mut I, action: Action) -> core2::io::Result<u64>
+where
+    E: Encoder<In = u8, Out = u8>,
+    CompressionError: From<E::Error>,
+    W: Write,
+    I: Iterator<Item = u8>,
+{
+    let mut output: Vec<u8> = Vec::with_capacity(INPUT_BUFFER_SIZE);
+    let mut n_written: u64 = 0;
+    while let Some(result) = encoder.next(input, action) {
+        output.push(result.map_err(compression_error)?);
+        if output.len() >= INPUT_BUFFER_SIZE {
+            write.write_all(&output)?;
+            n_written += output.len() as u64;
+            output.clear();
+        }
+    }
+    write.write_all(&output)?;
+    n_written += output.len() as u64;
+    return Ok(n_written);
+}
+
+/// Feeds `input` to the deflate compressor of `miniz_oxide` crate, writes its output and returns the number of bytes written
+///
+/// The deflate stream is terminated if `flush` is `MZFlush::Finish`.
+#[cfg(feature = "deflate-miniz")]
+fn deflate<W: Write>(
+    compressor: &mut CompressorOxide,
+    write: &mut W,
+    mut input: &[u8],
+    flush: MZFlush,
+) -> core2::io::Result<u64> {
+    let mut output: Vec<u8> = vec![0; INPUT_BUFFER_SIZE];
+    let mut n_written: u64 = 0;
+    loop {
+        let result = miniz_oxide::deflate::stream::deflate(compressor, input, &mut output, flush);
+        let status = match result.status {
+            Ok(status) => status,
+            // no progress without more input
+            Err(MZError::Buf) if flush == MZFlush::None => break,
+            Err(e) => return Err(compression_error(e)),
+        };
+        input = &input[result.bytes_consumed..];
+        write.write_all(&output[..result.bytes_written])?;
+        n_written += result.bytes_written as u64;
+        let is_output_full = result.bytes_written == output.len();
+        if status == MZStatus::StreamEnd || (flush == MZFlush::None && input.is_empty() && !is_output_full) {
+            break;
+        }
+    }
+    return Ok(n_written);
+}
+
+/// Compresses `data` into a zstd frame, writes it and returns the number of bytes written
+#[cfg(feature = "zstd")]
+fn write_zstd_frame<W: Write>(write: &mut W, data: &[u8], level: CompressionLevel) -> core2::io::Result<u64> {
+    let mut output: Vec<u8> = Vec::new();
+    ruzstd::encoding::compress(data, &mut output, level);
+    return write_output(write, &mut output);
+}
+
+/// Writes the compressed data buffered in `output`, empties it and returns the number of bytes written
+#[cfg(any(feature = "lzma", feature = "zstd"))]
+fn write_output<W: Write>(write: &mut W, output: &mut Vec<u8>) -> core2::io::Result<u64> {
+    write.write_all(output)?;
+    let n_written = output.len() as u64;
+    output.clear();
+    return Ok(n_written);
+}
+
+/// Converts an error of an encoder into an I/O error
+#[cfg(any(feature = "deflate-miniz", feature = "bzip2", feature = "lzma"))]
+fn compression_error<E>(_: E) -> Error {
+    return Error::new(ErrorKind::Other, "compression failed");
+}
+
+/// Writes a stored (non-compressed) deflate block and returns the number of bytes written
+///
+/// See 3.2.4 in RFC 1951.
+fn write_stored_block<W: Write>(write: &mut W, block: &[u8], is_final: bool) -> core2::io::Result<u64> {
+    let length = block.len() as u16;
+    // BFINAL and BTYPE = 00, padded to the byte boundary
+    write.write_all(&[is_final as u8])?;
+    write.write_all(&length.to_le_bytes())?;
+    write.write_all(&(!length).to_le_bytes())?;
+    write.write_all(block)?;
+    return Ok(5 + block.len() as u64);
+}
@@ -0,0 +1,71 @@
+/// Lookup table of CRC-32 (polynomial 0xEDB88320; see 4.4.7 in https://pkware.cachefly.net/webdocs/casestudies/APPNOTE.TXT)
+static CRC32_TABLE: [u32; 256] = make_crc32_table();
+
+const fn make_crc32_table() -> [u32; 256] {
+    let mut table: [u32; 256] = [0; 256];
+    let mut i: usize = 0;
+    while i < 256 {
+        let mut value = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            value = if value & 1 != 0 {
+                0xEDB8_8320 ^ (value >> 1)
+            } else {
+                value >> 1
+            };
+            bit += 1;
+        }
+        table[i] = value;
+        i += 1;
+    }
+    return table;
+}
+
+/// Feeds one byte into a raw (not inverted) CRC-32 state and returns the new state
+///
+/// Also used to update the keys of the traditional PKWARE encryption.
+pub(crate) fn update_crc32_state(state: u32, byte: u8) -> u32 {
+    return CRC32_TABLE[((state ^ byte as u32) & 0xFF) as usize] ^ (state >> 8);
+}
+
+/// Incremental CRC-32 calculator used in ZIP archives
+pub struct Crc32 {
+    /// Current value (not inverted)
+    state: u32,
+}
+
+impl Crc32 {
+    /// Generates a calculator for empty data
+    pub fn new() -> Self {
+        return Self { state: 0xFFFF_FFFF };
+    }
+
+    /// Feeds `data` into the calculator
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - next part of the data
+    pub fn update(&mut self, data: &[u8]) {
+        let mut state = self.state;
+        for byte in data {
+            state = update_crc32_state(state, *byte);
+        }
+        self.state = state;
+    }
+
+    /// Returns CRC-32 of the data fed so far
+    pub fn value(&self) -> u32 {
+        return !self.state;
+    }
+}
+
+/// Returns CRC-32 of `data`
+///
+/// # Arguments
+///
+/// * `data` - data to calculate CRC-32 of
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut calculator = Crc32::new();
+    calculator.update(data);
+    return calculator.value();
+}
@@ -0,0 +1,446 @@
+use super::zip_central_directory::DATA_DESCRIPTOR_EXISTS_FLAG_BIT;
+use super::zip_crc32::update_crc32_state;
+use super::zip_error::ZipReadError;
+#[cfg(feature = "aes")]
+use aes::cipher::{generic_array::GenericArray, BlockEncrypt, KeyInit};
+#[cfg(feature = "aes")]
+use aes::{Aes128, Aes192, Aes256};
+#[cfg(feature = "aes")]
+use hmac::{Hmac, Mac};
+#[cfg(feature = "aes")]
+use sha1::Sha1;
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::vec;
+use core2::io::{Error, ErrorKind, Read};
+
+/// Size of the encryption header in front of the encrypted file content of the traditional PKWARE encryption
+pub const ZIP_CRYPTO_HEADER_SIZE: u64 = 12;
+/// Compression method that indicates WinZip AES encryption (the actual one is in WinZip AES extra field)
+pub const COMPRESSION_METHOD_AES: u16 = 99;
+/// Version needed to extract WinZip AES encrypted files
+pub const AES_VERSION_REQUIRED_TO_EXTRACT: u16 = 51;
+/// Size of the password verification value after the salt
+pub const AES_PASSWORD_VERIFICATION_VALUE_SIZE: u64 = 2;
+/// Size of the authentication code after the encrypted file content
+pub const AES_AUTHENTICATION_CODE_SIZE: u64 = 10;
+/// Number of iterations of PBKDF2 for WinZip AES
+#[cfg(feature = "aes")]
+const AES_KEY_DERIVATION_ITERATIONS: u32 = 1000;
+/// Size of AES blocks
+#[cfg(feature = "aes")]
+const AES_BLOCK_SIZE: usize = 16;
+/// Message of the I/O error of `ZipDecryptReader` when the authentication code mismatches
+/// (converted into `ZipReadError::AuthenticationCodeMismatch`)
+pub(crate) const AUTHENTICATION_CODE_MISMATCH_MESSAGE: &str =
+    "authentication code of the encrypted file content mismatches";
+
+/// 従来のPKWARE暗号 (ZipCrypto) の鍵 /
+/// Keys of the traditional PKWARE encryption (ZipCrypto)
+///
+/// See 6.1 in https://pkware.cachefly.net/webdocs/casestudies/APPNOTE.TXT
+/// This encryption is weak; it is supported only to read existing archives.
+#[derive(Clone)]
+pub struct ZipCryptoKeys {
+    key0: u32,
+    key1: u32,
+    key2: u32,
+}
+
+impl ZipCryptoKeys {
+    /// Initializes the keys with a password
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - byte sequence of the password
+    pub fn new(password: &[u8]) -> Self {
+        let mut result = Self {
+            key0: 0x1234_5678,
+            key1: 0x2345_6789,
+            key2: 0x3456_7890,
+        };
+        for byte in password {
+            result.update(*byte);
+        }
+        return result;
+    }
+
+    /// Updates the keys with a plain byte
+    fn update(&mut self, byte: u8) {
+        self.key0 = update_crc32_state(self.key0, byte);
+        self.key1 = self
+            .key1
+            .wrapping_add(self.key0 & 0xFF)
+            .wrapping_mul(134_775_813)
+            .wrapping_add(1);
+        self.key2 = update_crc32_state(self.key2, (self.key1 >> 24) as u8);
+    }
+
+    /// Returns the next byte of the key stream
+    fn stream_byte(&self) -> u8 {
+        let temp = (self.key2 | 2) & 0xFFFF;
+        return (temp.wrapping_mul(temp ^ 1) >> 8) as u8;
+    }
+
+    /// Decrypts one byte and returns the plain byte
+    ///
+    /// # Arguments
+    ///
+    /// * `byte` - encrypted byte
+    pub fn decrypt_byte(&mut self, byte: u8) -> u8 {
+        let plain = byte ^ self.stream_byte();
+        self.update(plain);
+        return plain;
+    }
+
+    /// Decrypts `buf` in place
+    ///
+    /// # Arguments
+    ///
+    /// * `buf` - encrypted bytes
+    pub fn decrypt(&mut self, buf: &mut [u8]) {
+        for byte in buf {
+            *byte = self.decrypt_byte(*byte);
+        }
+    }
+}
+
+/// Returns the byte that the last byte of the decrypted encryption header must equal
+///
+/// It is the high byte of the last modification time if bit #3 of general purpose bit flag is set
+/// (CRC-32 is unknown when the header is written), or the high byte of CRC-32 otherwise.
+///
+/// # Arguments
+///
+/// * `general_purpose_flags` - general purpose bit flag of the local file header
+/// * `crc32` - CRC-32 of the file content
+/// * `last_mod_time` - last modification time in the local file header
+pub fn password_check_byte(general_purpose_flags: u16, crc32: u32, last_mod_time: u16) -> u8 {
+    if (DATA_DESCRIPTOR_EXISTS_FLAG_BIT & general_purpose_flags) != 0 {
+        return (last_mod_time >> 8) as u8;
+    }
+    return (crc32 >> 24) as u8;
+}
+
+/// Decryption state of `ZipDecryptReader`
+enum DecryptState {
+    Plain,
+    ZipCrypto(ZipCryptoKeys),
+    Aes {
+        cipher: Box<ZipAesCipher>,
+        /// authentication code stored after the encrypted data
+        authentication_code: [u8; AES_AUTHENTICATION_CODE_SIZE as usize],
+    },
+}
+
+/// `Read` adapter that decrypts the file content of a ZIP entry
+///
+/// Data that is not encrypted passes through, so that it can be put in front of `ZipDecompressReader` in any case.
+/// For WinZip AES, the authentication code is verified at the end of the data.
+pub struct ZipDecryptReader<R: Read> {
+    read: R,
+    state: DecryptState,
+}
+
+impl<R: Read> ZipDecryptReader<R> {
+    /// Generates a reader that passes through data that is not encrypted
+    ///
+    /// # Arguments
+    ///
+    /// * `read` - `Read` object of the file content
+    pub fn plain(read: R) -> Self {
+        return Self {
+            read,
+            state: DecryptState::Plain,
+        };
+    }
+
+    /// Reads the encryption header of the traditional PKWARE encryption, verifies the password
+    /// and generates a reader that decrypts the rest
+    ///
+    /// # Arguments
+    ///
+    /// * `read` - `Read` object of the encrypted file content (must be at the start of the encryption header)
+    /// * `password` - byte sequence of the password
+    /// * `check_byte` - see `password_check_byte`
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError::InvalidPassword` if the password is incorrect,
+    /// or `ZipReadError::IOError` if the encryption header cannot be read.
+    pub fn zip_crypto(mut read: R, password: &[u8], check_byte: u8) -> Result<Self, ZipReadError> {
+        let mut keys = ZipCryptoKeys::new(password);
+        let mut header: [u8; ZIP_CRYPTO_HEADER_SIZE as usize] = [0; ZIP_CRYPTO_HEADER_SIZE as usize];
+        read.read_exact(&mut header)?;
+        keys.decrypt(&mut header);
+        if header[header.len() - 1] != check_byte {
+            return Err(ZipReadError::InvalidPassword);
+        }
+        return Ok(Self {
+            read,
+            state: DecryptState::ZipCrypto(keys),
+        });
+    }
+
+    /// Reads the salt and password verification value of WinZip AES encryption, verifies the password
+    /// and generates a reader that decrypts the rest and verifies the authentication code at the end
+    ///
+    /// # Arguments
+    ///
+    /// * `read` - `Read` object of the encrypted file content (must be at the start of the salt and end before the authentication code)
+    /// * `password` - byte sequence of the password
+    /// * `strength` - strength in WinZip AES extra field
+    /// * `authentication_code` - authentication code after the encrypted file content
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError::InvalidPassword` if the password is incorrect,
+    /// `ZipReadError::UnsupportedZipArchive` if the strength is invalid or `aes` feature is disabled,
+    /// or `ZipReadError::IOError` if the salt cannot be read.
+    pub fn aes(
+        mut read: R,
+        password: &[u8],
+        strength: u8,
+        authentication_code: [u8; AES_AUTHENTICATION_CODE_SIZE as usize],
+    ) -> Result<Self, ZipReadError> {
+        let salt_size = aes_salt_size(strength).ok_or_else(|| ZipReadError::UnsupportedZipArchive {
+            reason: format!("AES strength {} is not supported", strength),
+        })?;
+        let mut salt = vec![0; salt_size];
+        read.read_exact(&mut salt)?;
+        let mut password_verification_value = [0; AES_PASSWORD_VERIFICATION_VALUE_SIZE as usize];
+        read.read_exact(&mut password_verification_value)?;
+        let (cipher, expected_password_verification_value) = ZipAesCipher::new(password, &salt, strength)?;
+        if password_verification_value != expected_password_verification_value {
+            return Err(ZipReadError::InvalidPassword);
+        }
+        return Ok(Self {
+            read,
+            state: DecryptState::Aes {
+                cipher: Box::new(cipher),
+                authentication_code,
+            },
+        });
+    }
+}
+
+impl<R: Read> Read for ZipDecryptReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> core2::io::Result<usize> {
+        let n_read = self.read.read(buf)?;
+        match &mut self.state {
+            DecryptState::Plain => {}
+            DecryptState::ZipCrypto(keys) => keys.decrypt(&mut buf[..n_read]),
+            DecryptState::Aes {
+                cipher,
+                authentication_code,
+            } => {
+                cipher.decrypt(&mut buf[..n_read]);
+                if n_read == 0 && !buf.is_empty() && !cipher.verify_authentication_code(authentication_code) {
+                    return Err(Error::new(ErrorKind::InvalidData, AUTHENTICATION_CODE_MISMATCH_MESSAGE));
+                }
+            }
+        }
+        return Ok(n_read);
+    }
+}
+
+/// Returns the size of the salt of WinZip AES encryption, or `None` if `strength` is invalid
+///
+/// # Arguments
+///
+/// * `strength` - 1: AES-128, 2: AES-192, 3: AES-256
+pub fn aes_salt_size(strength: u8) -> Option<usize> {
+    return match strength {
+        1 => Some(8),
+        2 => Some(12),
+        3 => Some(16),
+        _ => None,
+    };
+}
+
+/// AES block cipher of each key size
+#[cfg(feature = "aes")]
+enum AesBlockCipher {
+    Aes128(Aes128),
+    Aes192(Aes192),
+    Aes256(Aes256),
+}
+
+/// WinZipのAES暗号 (AE-1/AE-2) の暗号化・復号器 /
+/// Cipher of WinZip AES encryption (AE-1/AE-2)
+///
+/// The file content is encrypted with AES in CTR mode (little-endian counter starting at 1)
+/// and authenticated with HMAC-SHA1 of the encrypted data; see https://www.winzip.com/en/support/aes-encryption/
+#[cfg(feature = "aes")]
+pub struct ZipAesCipher {
+    cipher: AesBlockCipher,
+    hmac: Hmac<Sha1>,
+    /// counter of the last encrypted block
+    counter: u128,
+    /// key stream of the current block
+    key_stream: [u8; AES_BLOCK_SIZE],
+    /// position of the next byte in `key_stream`
+    key_stream_position: usize,
+}
+
+#[cfg(feature = "aes")]
+impl ZipAesCipher {
+    /// Derives the keys from a password and salt with PBKDF2-HMAC-SHA1,
+    /// and returns the cipher and the password verification value
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - byte sequence of the password
+    /// * `salt` - salt (its size depends on the strength; see `aes_salt_size`)
+    /// * `strength` - 1: AES-128, 2: AES-192, 3: AES-256
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError::UnsupportedZipArchive` if the strength is invalid or the size of the salt does not match it.
+    pub fn new(
+        password: &[u8],
+        salt: &[u8],
+        strength: u8,
+    ) -> Result<(Self, [u8; AES_PASSWORD_VERIFICATION_VALUE_SIZE as usize]), ZipReadError> {
+        if aes_salt_size(strength) != Some(salt.len()) {
+            return Err(ZipReadError::UnsupportedZipArchive {
+                reason: format!(
+                    "AES strength {} with {}-byte salt is not supported",
+                    strength,
+                    salt.len()
+                ),
+            });
+        }
+        // salt size is half of the key size
+        let key_size = salt.len() * 2;
+        let mut derived_key = vec![0; key_size * 2 + AES_PASSWORD_VERIFICATION_VALUE_SIZE as usize];
+        pbkdf2::pbkdf2::<Hmac<Sha1>>(password, salt, AES_KEY_DERIVATION_ITERATIONS, &mut derived_key)
+            .map_err(|_| ZipReadError::UnsupportedZipArchive {
+                reason: "AES key derivation failed".into(),
+            })?;
+        let encryption_key = &derived_key[..key_size];
+        let cipher = match strength {
+            1 => AesBlockCipher::Aes128(Aes128::new(GenericArray::from_slice(encryption_key))),
+            2 => AesBlockCipher::Aes192(Aes192::new(GenericArray::from_slice(encryption_key))),
+            _ => AesBlockCipher::Aes256(Aes256::new(GenericArray::from_slice(encryption_key))),
+        };
+        let hmac = <Hmac<Sha1> as Mac>::new_from_slice(&derived_key[key_size..key_size * 2])
+            .map_err(|_| ZipReadError::UnsupportedZipArchive {
+                reason: "AES authentication key is invalid".into(),
+            })?;
+        let password_verification_value = [derived_key[key_size * 2], derived_key[key_size * 2 + 1]];
+        return Ok((
+            Self {
+                cipher,
+                hmac,
+                counter: 0,
+                key_stream: [0; AES_BLOCK_SIZE],
+                key_stream_position: AES_BLOCK_SIZE,
+            },
+            password_verification_value,
+        ));
+    }
+
+    /// XORs `buf` with the key stream
+    fn apply_key_stream(&mut self, buf: &mut [u8]) {
+        for byte in buf {
+            if self.key_stream_position == AES_BLOCK_SIZE {
+                self.counter = self.counter.wrapping_add(1);
+                let mut block = GenericArray::clone_from_slice(&self.counter.to_le_bytes());
+                match &self.cipher {
+                    AesBlockCipher::Aes128(cipher) => cipher.encrypt_block(&mut block),
+                    AesBlockCipher::Aes192(cipher) => cipher.encrypt_block(&mut block),
+                    AesBlockCipher::Aes256(cipher) => cipher.encrypt_block(&mut block),
+                }
+                self.key_stream.copy_from_slice(&block);
+                self.key_stream_position = 0;
+            }
+            *byte ^= self.key_stream[self.key_stream_position];
+            self.key_stream_position += 1;
+        }
+    }
+
+    /// Encrypts `buf` in place
+    ///
+    /// # Arguments
+    ///
+    /// * `buf` - next part of the file content
+    pub fn encrypt(&mut self, buf: &mut [u8]) {
+        self.apply_key_stream(buf);
+        self.hmac.update(buf);
+    }
+
+    /// Decrypts `buf` in place
+    ///
+    /// # Arguments
+    ///
+    /// * `buf` - next part of the encrypted file content
+    pub fn decrypt(&mut self, buf: &mut [u8]) {
+        self.hmac.update(buf);
+        self.apply_key_stream(buf);
+    }
+
+    /// Returns the authentication code of the encrypted data processed so far
+    pub fn authentication_code(&self) -> [u8; AES_AUTHENTICATION_CODE_SIZE as usize] {
+        let mut result = [0; AES_AUTHENTICATION_CODE_SIZE as usize];
+        result.copy_from_slice(
+            &self.hmac.clone().finalize().into_bytes()[..AES_AUTHENTICATION_CODE_SIZE as usize],
+        );
+        return result;
+    }
+
+    /// Returns `true` if `authentication_code` matches the encrypted data processed so far
+    ///
+    /// Compared in constant time, so that the time taken does not reveal how many leading bytes match.
+    ///
+    /// # Arguments
+    ///
+    /// * `authentication_code` - authentication code stored after the encrypted data
+    pub fn verify_authentication_code(&self, authentication_code: &[u8]) -> bool {
+        return authentication_code.len() == AES_AUTHENTICATION_CODE_SIZE as usize
+            && self.hmac.clone().verify_truncated_left(authentication_code).is_ok();
+    }
+}
+
+/// Cipher of WinZip AES encryption, which cannot be generated without `aes` feature
+#[cfg(not(feature = "aes"))]
+pub enum ZipAesCipher {}
+
+#[cfg(not(feature = "aes"))]
+impl ZipAesCipher {
+    /// Always fails because `aes` feature is disabled
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError::UnsupportedZipArchive`.
+    pub fn new(
+        _password: &[u8],
+        _salt: &[u8],
+        _strength: u8,
+    ) -> Result<(Self, [u8; AES_PASSWORD_VERIFICATION_VALUE_SIZE as usize]), ZipReadError> {
+        return Err(ZipReadError::UnsupportedZipArchive {
+            reason: "WinZip AES encryption is not supported (`aes` feature is disabled)".into(),
+        });
+    }
+
+    /// Encrypts `buf` in place
+    pub fn encrypt(&mut self, _buf: &mut [u8]) {
+        match *self {}
+    }
+
+    /// Decrypts `buf` in place
+    pub fn decrypt(&mut self, _buf: &mut [u8]) {
+        match *self {}
+    }
+
+    /// Returns the authentication code of the encrypted data processed so far
+    pub fn authentication_code(&self) -> [u8; AES_AUTHENTICATION_CODE_SIZE as usize] {
+        match *self {}
+    }
+
+    /// Returns `true` if `authentication_code` matches the encrypted data processed so far
+    pub fn verify_authentication_code(&self, _authentication_code: &[u8]) -> bool {
+        match *self {}
+    }
+}
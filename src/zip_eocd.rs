@@ -1,4 +1,5 @@
 use super::zip_error::ZipReadError;
+use super::zip_extra_field::ZIP64_VERSION_REQUIRED_TO_EXTRACT;
 use byteorder::{ReadBytesExt, WriteBytesExt, LE};
 
 use core2::io::Read;
@@ -12,7 +13,210 @@ use alloc::string::ToString;
 use crate::read_ext::ReadExt;
 
 /// magick number of EOCD
-const EOCD_MAGIC: [u8; 4] = [0x50, 0x4b, 0x5, 0x6];
+pub(crate) const EOCD_MAGIC: [u8; 4] = [0x50, 0x4b, 0x5, 0x6];
+/// magick number of ZIP64 EOCD record
+pub(crate) const ZIP64_EOCD_MAGIC: [u8; 4] = [0x50, 0x4b, 0x6, 0x6];
+/// magick number of ZIP64 EOCD locator
+const ZIP64_EOCD_LOCATOR_MAGIC: [u8; 4] = [0x50, 0x4b, 0x6, 0x7];
+/// size of ZIP64 EOCD locator (including magick number)
+const ZIP64_EOCD_LOCATOR_SIZE: u64 = 20;
+/// size of fixed fields of ZIP64 EOCD record after "size of ZIP64 EOCD record" field
+const ZIP64_EOCD_FIXED_FIELDS_SIZE: u64 = 44;
+/// size of ZIP64 EOCD record without extensible data (including magick number)
+const ZIP64_EOCD_MIN_SIZE: u64 = 56;
+/// size of EOCD without comment (including magick number)
+const EOCD_MIN_SIZE: u64 = 22;
+
+/// ZIP64 EOCD locator 情報を保持する構造体 /
+/// Structure that contains ZIP64 EOCD locator information
+///
+/// See 4.3.15 in https://pkware.cachefly.net/webdocs/casestudies/APPNOTE.TXT
+pub struct ZipEOCD64Locator {
+    /// ZIP64 EOCDレコードが存在するディスク番号 (0起算) /
+    /// number of the disk with the start of the ZIP64 EOCD record (0-based)
+    pub zip64_eocd_disk_index: u32,
+    /// ZIP64 EOCDレコードの開始位置 (絶対・0起算) /
+    /// offset of the ZIP64 EOCD record (absolute value; 0-based)
+    pub zip64_eocd_position: u64,
+    /// ディスク総数 /
+    /// total number of disks
+    pub n_disks: u32,
+
+    // ZIP64 EOCD locatorのエントリここまで / End of ZIP64 EOCD locator entries
+    /// ZIP64 EOCD locatorの開始位置 (マジックナンバー) /
+    /// (magick number of) ZIP64 EOCD locator starting position
+    pub starting_position_with_signature: u64,
+}
+
+impl ZipEOCD64Locator {
+    /// Reads from next to the signature (magick number) of the ZIP64 EOCD locator.
+    ///
+    /// # Arguments
+    ///
+    /// * `read` - `Read` object (must be at the next to the signature)
+    /// * `starting_position_with_signature` - position of the signature
+    fn read_without_signature<T: ReadBytesExt>(
+        read: &mut T,
+        starting_position_with_signature: u64,
+    ) -> Result<Self, ZipReadError> {
+        return Ok(Self {
+            zip64_eocd_disk_index: read.read_u32::<LE>()?,
+            zip64_eocd_position: read.read_u64::<LE>()?,
+            n_disks: read.read_u32::<LE>()?,
+            starting_position_with_signature,
+        });
+    }
+
+    /// Writes ZIP64 EOCD locator to stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails.
+    pub fn write<T: WriteBytesExt>(&self, write: &mut T) -> core2::io::Result<()> {
+        write.write_all(&ZIP64_EOCD_LOCATOR_MAGIC)?;
+        write.write_u32::<LE>(self.zip64_eocd_disk_index)?;
+        write.write_u64::<LE>(self.zip64_eocd_position)?;
+        write.write_u32::<LE>(self.n_disks)?;
+        return Ok(());
+    }
+}
+
+/// ZIP64 EOCD レコード情報を保持する構造体 /
+/// Structure that contains ZIP64 EOCD record information
+///
+/// See 4.3.14 in https://pkware.cachefly.net/webdocs/casestudies/APPNOTE.TXT
+pub struct ZipEOCD64 {
+    /// ZIP64 EOCDレコードのサイズ (先頭12バイトを除く) /
+    /// size of the ZIP64 EOCD record (excluding the leading 12 bytes)
+    pub record_size: u64,
+    /// As the name implies; see 4.4.2 in https://pkware.cachefly.net/webdocs/casestudies/APPNOTE.TXT
+    pub version_made_by: u16,
+    /// As the name implies; see 4.4.3 in https://pkware.cachefly.net/webdocs/casestudies/APPNOTE.TXT
+    pub version_required_to_extract: u16,
+    /// EOCDが存在するディスク番号 (0起算) /
+    /// number of the disk where EOCD exists (0-based)
+    pub eocd_disk_index: u32,
+    /// セントラルディレクトリが始まるディスク番号 (0起算) /
+    /// number of the disk with the start of the central directory (0-based)
+    pub cd_start_disk_index: u32,
+    /// EOCDがあるディスク内のセントラルディレクトリ総数 /
+    /// total number of entries in the central directory on the disk whare EOCD exists
+    pub n_cd_entries_in_disk: u64,
+    /// セントラルディレクトリ総数 /
+    /// total number of entries in the central directory
+    pub n_cd_entries: u64,
+    /// セントラルディレクトリのサイズ /
+    /// size of the central directory
+    pub cd_size: u64,
+    /// セントラルディレクトリ開始位置 (絶対・0起算)
+    /// offset of start of central directory (with respect to the starting disk number) (absolute value; 0-based)
+    pub cd_starting_position: u64,
+    /// 拡張データ /
+    /// ZIP64 extensible data sector
+    pub extensible_data: Vec<u8>,
+
+    // ZIP64 EOCDレコードのエントリここまで / End of ZIP64 EOCD record entries
+    /// ZIP64 EOCDレコードの開始位置 (マジックナンバー) /
+    /// (magick number of) ZIP64 EOCD record starting position
+    pub starting_position_with_signature: u64,
+    /// ZIP64 EOCDレコードの開始位置 (マジックナンバーすぐ次) /
+    /// ZIP64 EOCD record starting position (next to magick number)
+    pub starting_position_without_signature: u64,
+}
+
+impl ZipEOCD64 {
+    /// Examines the signature, reads the ZIP64 EOCD record and returns an instance that represents it
+    ///
+    /// # Arguments
+    ///
+    /// * `read` - file handler (must be at the head of the signature)
+    /// * `locator_position` - position of the ZIP64 EOCD locator, which the record must end before
+    fn read_and_generate_from_signature<T: ReadBytesExt + core2::io::Seek>(
+        read: &mut T,
+        locator_position: u64,
+    ) -> Result<Self, ZipReadError> {
+        let mut signature_candidate: [u8; 4] = [0; 4];
+        let starting_position_with_signature = read.seek(SeekFrom::Current(0))?;
+        read.read_exact(&mut signature_candidate)?;
+        if signature_candidate != ZIP64_EOCD_MAGIC {
+            return Err(ZipReadError::InvalidZipArchive {
+                reason: format!(
+                    "assumed ZIP64 end of central directory signature doesn't appear at position {}",
+                    starting_position_with_signature
+                ),
+            });
+        }
+        let mut result = Self {
+            record_size: read.read_u64::<LE>()?,
+            version_made_by: read.read_u16::<LE>()?,
+            version_required_to_extract: read.read_u16::<LE>()?,
+            eocd_disk_index: read.read_u32::<LE>()?,
+            cd_start_disk_index: read.read_u32::<LE>()?,
+            n_cd_entries_in_disk: read.read_u64::<LE>()?,
+            n_cd_entries: read.read_u64::<LE>()?,
+            cd_size: read.read_u64::<LE>()?,
+            cd_starting_position: read.read_u64::<LE>()?,
+            extensible_data: vec![],
+            starting_position_with_signature,
+            starting_position_without_signature: starting_position_with_signature
+                + ZIP64_EOCD_MAGIC.len() as u64,
+        };
+        let extensible_data_length = result
+            .record_size
+            .checked_sub(ZIP64_EOCD_FIXED_FIELDS_SIZE)
+            .ok_or_else(|| ZipReadError::InvalidZipArchive {
+                reason: format!(
+                    "size of ZIP64 end of central directory record is too small ({})",
+                    result.record_size
+                ),
+            })?;
+        // `record_size` is not trusted before allocating the buffer for the extensible data
+        let max_extensible_data_length =
+            locator_position.saturating_sub(starting_position_with_signature + ZIP64_EOCD_MIN_SIZE);
+        if extensible_data_length > max_extensible_data_length {
+            return Err(ZipReadError::InvalidZipArchive {
+                reason: format!(
+                    "size of ZIP64 end of central directory record is too large ({})",
+                    result.record_size
+                ),
+            });
+        }
+        let read_extensible_data_length = read
+            .take(extensible_data_length)
+            .read_to_end(&mut result.extensible_data)?;
+        if read_extensible_data_length as u64 != extensible_data_length {
+            return Err(ZipReadError::InvalidZipArchive {
+                reason: format!(
+                    "ZIP64 extensible data length is invalid (expected from length value field: {} / got {}",
+                    extensible_data_length, read_extensible_data_length
+                ),
+            });
+        }
+        return Ok(result);
+    }
+
+    /// Writes ZIP64 EOCD record to stream.
+    ///
+    /// `record_size` is ignored and recalculated from the length of `extensible_data`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails.
+    pub fn write<T: WriteBytesExt>(&self, write: &mut T) -> core2::io::Result<()> {
+        write.write_all(&ZIP64_EOCD_MAGIC)?;
+        write.write_u64::<LE>(ZIP64_EOCD_FIXED_FIELDS_SIZE + self.extensible_data.len() as u64)?;
+        write.write_u16::<LE>(self.version_made_by)?;
+        write.write_u16::<LE>(self.version_required_to_extract)?;
+        write.write_u32::<LE>(self.eocd_disk_index)?;
+        write.write_u32::<LE>(self.cd_start_disk_index)?;
+        write.write_u64::<LE>(self.n_cd_entries_in_disk)?;
+        write.write_u64::<LE>(self.n_cd_entries)?;
+        write.write_u64::<LE>(self.cd_size)?;
+        write.write_u64::<LE>(self.cd_starting_position)?;
+        write.write_all(self.extensible_data.as_slice())?;
+        return Ok(());
+    }
+}
 
 /// EOCD (End of Central Directory) 情報を保持する構造体 /
 /// Structure that contains EOCD (End of Central Directory) information
@@ -41,6 +245,12 @@ pub struct ZipEOCD {
     /// ZIPコメント
     /// ZIP file comment
     pub comment: Vec<u8>,
+    /// ZIP64 EOCD locator (ZIP64の場合のみ存在) /
+    /// ZIP64 EOCD locator (exists only in ZIP64 archives)
+    pub zip64_locator: Option<ZipEOCD64Locator>,
+    /// ZIP64 EOCDレコード (ZIP64の場合のみ存在) /
+    /// ZIP64 EOCD record (exists only in ZIP64 archives)
+    pub zip64: Option<ZipEOCD64>,
 
     // EOCDのエントリここまで / End of EOCD entries
     /// EOCDの開始位置 (マジックナンバー) /
@@ -87,6 +297,27 @@ impl ZipEOCD {
         return Ok(true);
     }
 
+    /// EOCDの直前にZIP64 EOCD locatorがあれば、それとZIP64 EOCDレコードを読み込む /
+    /// Reads the ZIP64 EOCD locator just before the EOCD and the ZIP64 EOCD record it points to, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `read` - `Read` object (the read position does not matter)
+    /// * `absolute_position` - see `from_reader_with_disk_positions`
+    fn read_zip64_records<T: ReadBytesExt + core2::io::Seek>(
+        &mut self,
+        read: &mut T,
+        absolute_position: &dyn Fn(u32, u64) -> Result<u64, ZipReadError>,
+    ) -> Result<(), ZipReadError> {
+        if let Some((locator, zip64)) =
+            read_zip64_records(read, self.starting_position_with_signature, absolute_position)?
+        {
+            self.zip64 = Some(zip64);
+            self.zip64_locator = Some(locator);
+        }
+        return Ok(());
+    }
+
     ///空のEOCDオブジェクトを生成 /
     /// Generates an empty EOCD object
     fn empty() -> ZipEOCD {
@@ -99,17 +330,146 @@ impl ZipEOCD {
             cd_starting_position: 0,
             comment_length: 0,
             comment: vec![],
+            zip64_locator: None,
+            zip64: None,
             starting_position_with_signature: 0,
             starting_position_without_signature: 0,
         };
     }
 
+    /// 単一ファイルのZIPアーカイブ用のEOCDオブジェクトを生成 /
+    /// Generates an EOCD object for a single (not splitted) ZIP archive.
+    ///
+    /// If any of the values does not fit in the classic EOCD, ZIP64 EOCD record and locator are also generated.
+    /// The central directory is assumed to be followed immediately by them.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_cd_entries` - total number of entries in the central directory
+    /// * `cd_size` - size of the central directory
+    /// * `cd_starting_position` - **absolute** position of the start of the central directory
+    pub fn new(n_cd_entries: u64, cd_size: u64, cd_starting_position: u64) -> Self {
+        return Self::new_split(
+            n_cd_entries,
+            n_cd_entries,
+            cd_size,
+            0,
+            cd_starting_position,
+            0,
+            cd_starting_position + cd_size,
+        );
+    }
+
+    /// 分割されたZIPアーカイブの最後のボリューム用のEOCDオブジェクトを生成 /
+    /// Generates an EOCD object for the last volume of a split ZIP archive.
+    ///
+    /// Positions are offsets from the start of the disk.
+    /// If any of the values does not fit in the classic EOCD, ZIP64 EOCD record and locator are also generated,
+    /// which start at `starting_position` and are followed by EOCD.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_cd_entries` - total number of entries in the central directory
+    /// * `n_cd_entries_in_disk` - number of entries in the central directory on the last disk
+    /// * `cd_size` - size of the central directory
+    /// * `cd_start_disk_index` - number of the disk with the start of the central directory (0-based)
+    /// * `cd_starting_position` - offset of the start of the central directory in that disk
+    /// * `eocd_disk_index` - number of the last disk, where EOCD is written (0-based)
+    /// * `starting_position` - offset in the last disk where the ZIP64 EOCD record (if any) or EOCD starts
+    pub fn new_split(
+        n_cd_entries: u64,
+        n_cd_entries_in_disk: u64,
+        cd_size: u64,
+        cd_start_disk_index: u32,
+        cd_starting_position: u64,
+        eocd_disk_index: u32,
+        starting_position: u64,
+    ) -> Self {
+        let mut result = Self::empty();
+        let needs_zip64 = n_cd_entries >= u16::MAX as u64
+            || n_cd_entries_in_disk >= u16::MAX as u64
+            || cd_size >= u32::MAX as u64
+            || cd_starting_position >= u32::MAX as u64
+            || cd_start_disk_index >= u16::MAX as u32
+            || eocd_disk_index >= u16::MAX as u32;
+        result.eocd_disk_index = core::cmp::min(eocd_disk_index, u16::MAX as u32) as u16;
+        result.cd_start_disk_index = core::cmp::min(cd_start_disk_index, u16::MAX as u32) as u16;
+        result.n_cd_entries = core::cmp::min(n_cd_entries, u16::MAX as u64) as u16;
+        result.n_cd_entries_in_disk = core::cmp::min(n_cd_entries_in_disk, u16::MAX as u64) as u16;
+        result.cd_size = core::cmp::min(cd_size, u32::MAX as u64) as u32;
+        result.cd_starting_position = core::cmp::min(cd_starting_position, u32::MAX as u64) as u32;
+        result.starting_position_with_signature = starting_position;
+        if needs_zip64 {
+            result.zip64 = Some(ZipEOCD64 {
+                record_size: ZIP64_EOCD_FIXED_FIELDS_SIZE,
+                version_made_by: ZIP64_VERSION_REQUIRED_TO_EXTRACT,
+                version_required_to_extract: ZIP64_VERSION_REQUIRED_TO_EXTRACT,
+                eocd_disk_index,
+                cd_start_disk_index,
+                n_cd_entries_in_disk,
+                n_cd_entries,
+                cd_size,
+                cd_starting_position,
+                extensible_data: vec![],
+                starting_position_with_signature: starting_position,
+                starting_position_without_signature: starting_position
+                    + ZIP64_EOCD_MAGIC.len() as u64,
+            });
+            result.zip64_locator = Some(ZipEOCD64Locator {
+                zip64_eocd_disk_index: eocd_disk_index,
+                zip64_eocd_position: starting_position,
+                n_disks: eocd_disk_index + 1,
+                starting_position_with_signature: starting_position + ZIP64_EOCD_MIN_SIZE,
+            });
+            result.starting_position_with_signature =
+                starting_position + ZIP64_EOCD_MIN_SIZE + ZIP64_EOCD_LOCATOR_SIZE;
+        }
+        result.starting_position_without_signature =
+            result.starting_position_with_signature + EOCD_MAGIC.len() as u64;
+        return result;
+    }
+
+    /// Replaces the ZIP file comment
+    ///
+    /// # Arguments
+    ///
+    /// * `comment` - Slice of new comment
+    pub fn set_comment_from_slice(&mut self, comment: &Vec<u8>) {
+        self.comment_length = comment.len() as u16;
+        self.comment.clone_from(comment);
+    }
+
+    /// Returns the number of bytes written by `write` (including ZIP64 EOCD record and locator if any)
+    pub fn size(&self) -> u64 {
+        let mut result = EOCD_MIN_SIZE + self.comment.len() as u64;
+        if let Some(zip64) = &self.zip64 {
+            result += ZIP64_EOCD_MIN_SIZE + zip64.extensible_data.len() as u64 + ZIP64_EOCD_LOCATOR_SIZE;
+        }
+        return result;
+    }
+
     /// Writes EOCD to stream.
     ///
+    /// If `zip64` exists, ZIP64 EOCD record and locator are written before EOCD.
+    /// The locator is generated from `zip64` if `zip64_locator` is `None`.
+    ///
     /// # Errors
     ///
     /// Returns an error if writing fails.
     pub fn write<T: WriteBytesExt>(&self, write: &mut T) -> core2::io::Result<()> {
+        if let Some(zip64) = &self.zip64 {
+            zip64.write(write)?;
+            match &self.zip64_locator {
+                Some(zip64_locator) => zip64_locator.write(write)?,
+                None => ZipEOCD64Locator {
+                    zip64_eocd_disk_index: zip64.eocd_disk_index,
+                    zip64_eocd_position: zip64.starting_position_with_signature,
+                    n_disks: zip64.eocd_disk_index + 1,
+                    starting_position_with_signature: 0,
+                }
+                .write(write)?,
+            }
+        }
         write.write_all(&EOCD_MAGIC)?;
         write.write_u16::<LE>(self.eocd_disk_index)?;
         write.write_u16::<LE>(self.cd_start_disk_index)?;
@@ -124,6 +484,19 @@ impl ZipEOCD {
 
     pub fn from_reader<T: ReadBytesExt + core2::io::Seek>(
         read: &mut T,
+    ) -> Result<ZipEOCD, ZipReadError> {
+        return Self::from_reader_with_disk_positions(read, &|_, position| Ok(position));
+    }
+
+    /// Same as `from_reader`, but the position of the ZIP64 EOCD record in the locator is converted by `absolute_position`
+    ///
+    /// # Arguments
+    ///
+    /// * `read` - `Read` object of the whole archive (e.g. `ZipMultiVolumeReader`)
+    /// * `absolute_position` - function that converts a disk number and an offset in the disk into a position in `read`
+    pub(crate) fn from_reader_with_disk_positions<T: ReadBytesExt + core2::io::Seek>(
+        read: &mut T,
+        absolute_position: &dyn Fn(u32, u64) -> Result<u64, ZipReadError>,
     ) -> Result<ZipEOCD, ZipReadError> {
         let mut eocd = ZipEOCD::empty();
         let zip_size = read.seek(SeekFrom::End(0))?;
@@ -157,6 +530,7 @@ impl ZipEOCD {
             // magick numbers found
             if eocd_magic_point >= EOCD_MAGIC.len() {
                 if eocd.from_reader_next_to_signature(read)? {
+                    eocd.read_zip64_records(read, absolute_position)?;
                     return Ok(eocd);
                 }
                 // not magick numbers
@@ -173,17 +547,82 @@ impl ZipEOCD {
         });
     }
 
+    /// EOCDが存在するディスク番号 (ZIP64 EOCDレコードがあればその値) /
+    /// Returns the number of the disk where EOCD exists, preferring the ZIP64 EOCD record if any
+    pub fn effective_eocd_disk_index(&self) -> u32 {
+        return match &self.zip64 {
+            Some(zip64) => zip64.eocd_disk_index,
+            None => self.eocd_disk_index as u32,
+        };
+    }
+
+    /// セントラルディレクトリが始まるディスク番号 (ZIP64 EOCDレコードがあればその値) /
+    /// Returns the number of the disk with the start of the central directory, preferring the ZIP64 EOCD record if any
+    pub fn effective_cd_start_disk_index(&self) -> u32 {
+        return match &self.zip64 {
+            Some(zip64) => zip64.cd_start_disk_index,
+            None => self.cd_start_disk_index as u32,
+        };
+    }
+
+    /// EOCDがあるディスク内のセントラルディレクトリ総数 (ZIP64 EOCDレコードがあればその値) /
+    /// Returns the number of entries in the central directory on the disk where EOCD exists, preferring the ZIP64 EOCD record if any
+    pub fn effective_n_cd_entries_in_disk(&self) -> u64 {
+        return match &self.zip64 {
+            Some(zip64) => zip64.n_cd_entries_in_disk,
+            None => self.n_cd_entries_in_disk as u64,
+        };
+    }
+
+    /// セントラルディレクトリ総数 (ZIP64 EOCDレコードがあればその値) /
+    /// Returns the total number of entries in the central directory, preferring the ZIP64 EOCD record if any
+    pub fn effective_n_cd_entries(&self) -> u64 {
+        return match &self.zip64 {
+            Some(zip64) => zip64.n_cd_entries,
+            None => self.n_cd_entries as u64,
+        };
+    }
+
+    /// セントラルディレクトリのサイズ (ZIP64 EOCDレコードがあればその値) /
+    /// Returns the size of the central directory, preferring the ZIP64 EOCD record if any
+    pub fn effective_cd_size(&self) -> u64 {
+        return match &self.zip64 {
+            Some(zip64) => zip64.cd_size,
+            None => self.cd_size as u64,
+        };
+    }
+
+    /// セントラルディレクトリ開始位置 (ZIP64 EOCDレコードがあればその値) /
+    /// Returns the offset of start of central directory, preferring the ZIP64 EOCD record if any
+    pub fn effective_cd_starting_position(&self) -> u64 {
+        return match &self.zip64 {
+            Some(zip64) => zip64.cd_starting_position,
+            None => self.cd_starting_position as u64,
+        };
+    }
+
+    /// セントラルディレクトリの直後の位置 (ZIP64 EOCDレコードまたはEOCDの開始位置) /
+    /// Returns the position just after the central directory (the start of the ZIP64 EOCD record or EOCD)
+    pub fn cd_ending_position(&self) -> u64 {
+        return match &self.zip64 {
+            Some(zip64) => zip64.starting_position_with_signature,
+            None => self.starting_position_with_signature,
+        };
+    }
+
     /// 分割されたZIPファイルでなければtrue /
     /// Returns `true` if ZIP archive is NOT splitted
     pub fn is_single_archive(&self) -> bool {
-        return self.eocd_disk_index == 0 && self.n_cd_entries == self.n_cd_entries_in_disk;
+        return self.effective_eocd_disk_index() == 0
+            && self.effective_n_cd_entries() == self.effective_n_cd_entries_in_disk();
     }
 
     /// ZIP64ならtrue /
     /// Returns `true` if ZIP archive is ZIP64
     pub fn is_zip64(&self) -> bool {
         // Prioritize the ones that are likely to overflow.
-        return self.cd_starting_position == u32::MAX
+        return self.zip64.is_some()
+            || self.cd_starting_position == u32::MAX
             || self.cd_size == u32::MAX
             || self.n_cd_entries == u16::MAX
             || self.n_cd_entries_in_disk == u16::MAX
@@ -202,11 +641,177 @@ impl ZipEOCD {
                 reason: "it is one of splitted arvhives".to_string(),
             });
         }
-        if self.is_zip64() {
-            return Err(ZipReadError::UnsupportedZipArchive {
-                reason: "it is ZIP64 formatted".to_string(),
+        if self.is_zip64() && self.zip64.is_none() {
+            return Err(ZipReadError::InvalidZipArchive {
+                reason: "it is ZIP64 formatted but ZIP64 end of central directory record was not found".to_string(),
             });
         }
         return Ok(());
     }
 }
+
+/// EOCDの直前にZIP64 EOCD locatorがあれば、それとZIP64 EOCDレコードを読み込む /
+/// Reads the ZIP64 EOCD locator just before the EOCD and the ZIP64 EOCD record it points to, if any.
+///
+/// # Arguments
+///
+/// * `read` - `Read` object (the read position does not matter)
+/// * `eocd_position` - position of the signature of the EOCD
+/// * `absolute_position` - function that converts the disk number and offset in the locator into a position in `read`
+fn read_zip64_records<T: ReadBytesExt + core2::io::Seek>(
+    read: &mut T,
+    eocd_position: u64,
+    absolute_position: &dyn Fn(u32, u64) -> Result<u64, ZipReadError>,
+) -> Result<Option<(ZipEOCD64Locator, ZipEOCD64)>, ZipReadError> {
+    let locator_position = match eocd_position.checked_sub(ZIP64_EOCD_LOCATOR_SIZE) {
+        Some(pos) => pos,
+        None => return Ok(None),
+    };
+    read.seek(SeekFrom::Start(locator_position))?;
+    let mut signature_candidate: [u8; 4] = [0; 4];
+    read.read_exact(&mut signature_candidate)?;
+    if signature_candidate != ZIP64_EOCD_LOCATOR_MAGIC {
+        return Ok(None);
+    }
+    let locator = ZipEOCD64Locator::read_without_signature(read, locator_position)?;
+    read.seek(SeekFrom::Start(absolute_position(
+        locator.zip64_eocd_disk_index,
+        locator.zip64_eocd_position,
+    )?))?;
+    let zip64 = ZipEOCD64::read_and_generate_from_signature(read, locator_position)?;
+    return Ok(Some((locator, zip64)));
+}
+
+/// バイト列からコメントを借用するEOCD情報の構造体 /
+/// Structure that contains EOCD information borrowing the comment from a byte sequence
+///
+/// Generated by `from_bytes` without copying; see `ZipEOCD` for the meaning of the fields.
+pub struct ZipEOCDRef<'a> {
+    pub eocd_disk_index: u16,
+    pub cd_start_disk_index: u16,
+    pub n_cd_entries_in_disk: u16,
+    pub n_cd_entries: u16,
+    pub cd_size: u32,
+    pub cd_starting_position: u32,
+    pub comment_length: u16,
+    /// ZIPコメント (借用) /
+    /// ZIP file comment (borrowed)
+    pub comment: &'a [u8],
+    pub zip64_locator: Option<ZipEOCD64Locator>,
+    pub zip64: Option<ZipEOCD64>,
+    pub starting_position_with_signature: u64,
+    pub starting_position_without_signature: u64,
+}
+
+impl<'a> ZipEOCDRef<'a> {
+    /// メモリ上のZIPアーカイブ全体からEOCDを探して読み込む /
+    /// Finds and reads EOCD from a whole ZIP archive on memory
+    ///
+    /// # Arguments
+    ///
+    /// * `archive` - byte sequence of the whole ZIP archive
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError::InvalidZipArchive` if EOCD is not found or the ZIP64 records are broken.
+    pub fn from_bytes(archive: &'a [u8]) -> Result<Self, ZipReadError> {
+        // fixed fields including the signature
+        const EOCD_FIXED_SIZE: usize = 22;
+        // comment is 65535 bytes at most
+        let left_bound = archive.len().saturating_sub(u16::MAX as usize + EOCD_FIXED_SIZE);
+        let right_bound = archive.len().saturating_sub(EOCD_FIXED_SIZE - 1);
+        for pos in left_bound..right_bound {
+            if archive[pos..pos + EOCD_MAGIC.len()] != EOCD_MAGIC {
+                continue;
+            }
+            let mut read = &archive[pos + EOCD_MAGIC.len()..];
+            let mut result = Self {
+                eocd_disk_index: read.read_u16::<LE>()?,
+                cd_start_disk_index: read.read_u16::<LE>()?,
+                n_cd_entries_in_disk: read.read_u16::<LE>()?,
+                n_cd_entries: read.read_u16::<LE>()?,
+                cd_size: read.read_u32::<LE>()?,
+                cd_starting_position: read.read_u32::<LE>()?,
+                comment_length: read.read_u16::<LE>()?,
+                comment: &[],
+                zip64_locator: None,
+                zip64: None,
+                starting_position_with_signature: pos as u64,
+                starting_position_without_signature: (pos + EOCD_MAGIC.len()) as u64,
+            };
+            // the comment must end at the end of the archive
+            if read.len() != result.comment_length as usize {
+                continue;
+            }
+            result.comment = read;
+            if let Some((locator, zip64)) = read_zip64_records(
+                &mut core2::io::Cursor::new(archive),
+                result.starting_position_with_signature,
+                &|_, position| Ok(position),
+            )? {
+                result.zip64 = Some(zip64);
+                result.zip64_locator = Some(locator);
+            }
+            return Ok(result);
+        }
+        return Err(ZipReadError::InvalidZipArchive {
+            reason: "valid end of central directory signature (PK\\x05\\x06) was not found"
+                .to_string(),
+        });
+    }
+
+    /// セントラルディレクトリ総数 (ZIP64 EOCDレコードがあればその値) /
+    /// Returns the total number of entries in the central directory, preferring the ZIP64 EOCD record if any
+    pub fn effective_n_cd_entries(&self) -> u64 {
+        return match &self.zip64 {
+            Some(zip64) => zip64.n_cd_entries,
+            None => self.n_cd_entries as u64,
+        };
+    }
+
+    /// セントラルディレクトリのサイズ (ZIP64 EOCDレコードがあればその値) /
+    /// Returns the size of the central directory, preferring the ZIP64 EOCD record if any
+    pub fn effective_cd_size(&self) -> u64 {
+        return match &self.zip64 {
+            Some(zip64) => zip64.cd_size,
+            None => self.cd_size as u64,
+        };
+    }
+
+    /// セントラルディレクトリ開始位置 (ZIP64 EOCDレコードがあればその値) /
+    /// Returns the offset of start of central directory, preferring the ZIP64 EOCD record if any
+    pub fn effective_cd_starting_position(&self) -> u64 {
+        return match &self.zip64 {
+            Some(zip64) => zip64.cd_starting_position,
+            None => self.cd_starting_position as u64,
+        };
+    }
+
+    /// セントラルディレクトリの直後の位置 (ZIP64 EOCDレコードまたはEOCDの開始位置) /
+    /// Returns the position just after the central directory (the start of the ZIP64 EOCD record or EOCD)
+    pub fn cd_ending_position(&self) -> u64 {
+        return match &self.zip64 {
+            Some(zip64) => zip64.starting_position_with_signature,
+            None => self.starting_position_with_signature,
+        };
+    }
+
+    /// コメントを複製して`ZipEOCD`に変換する /
+    /// Converts into `ZipEOCD` by copying the comment
+    pub fn into_owned(self) -> ZipEOCD {
+        return ZipEOCD {
+            eocd_disk_index: self.eocd_disk_index,
+            cd_start_disk_index: self.cd_start_disk_index,
+            n_cd_entries_in_disk: self.n_cd_entries_in_disk,
+            n_cd_entries: self.n_cd_entries,
+            cd_size: self.cd_size,
+            cd_starting_position: self.cd_starting_position,
+            comment_length: self.comment_length,
+            comment: self.comment.to_vec(),
+            zip64_locator: self.zip64_locator,
+            zip64: self.zip64,
+            starting_position_with_signature: self.starting_position_with_signature,
+            starting_position_without_signature: self.starting_position_without_signature,
+        };
+    }
+}
@@ -1,3 +1,5 @@
+use super::zip_crypto::AUTHENTICATION_CODE_MISMATCH_MESSAGE;
+
 /// Extended `std::io::Error` for ZIP archive
 
 pub enum ZipReadError {
@@ -10,6 +12,15 @@ pub enum ZipReadError {
     /// An error due to unsupported ZIP archive in this software
     //#[error("this ZIP archive is not supported because: {reason}")]
     UnsupportedZipArchive { reason: alloc::string::String },
+    /// An error due to CRC-32 mismatch of the file content
+    //#[error("CRC-32 of the file content is {actual:08x}, but {expected:08x} is recorded")]
+    Crc32Mismatch { expected: u32, actual: u32 },
+    /// An error due to a wrong password of an encrypted file
+    //#[error("the password is incorrect")]
+    InvalidPassword,
+    /// An error due to authentication code mismatch of a WinZip AES encrypted file (tampered or broken)
+    //#[error("authentication code of the encrypted file content mismatches")]
+    AuthenticationCodeMismatch,
 }
 
 impl core::fmt::Display for ZipReadError {
@@ -26,11 +37,32 @@ impl core::fmt::Display for ZipReadError {
                 __formatter.write_fmt(format_args!("this ZIP archive is not supported because: {}",&reason))
                 
             }
+            ZipReadError::Crc32Mismatch { expected, actual } => {
+
+                __formatter.write_fmt(format_args!("CRC-32 of the file content is {:08x}, but {:08x} is recorded",&actual,&expected))
+
+            }
+            ZipReadError::InvalidPassword => {
+
+                __formatter.write_fmt(format_args!("the password is incorrect"))
+
+            }
+            ZipReadError::AuthenticationCodeMismatch => {
+
+                __formatter.write_fmt(format_args!("authentication code of the encrypted file content mismatches"))
+
+            }
         }
     }
 }
 impl core::convert::From<core2::io::Error> for ZipReadError {
     fn from(source: core2::io::Error) -> Self {
+        // core2 carries only a message in I/O errors
+        if source.kind() == core2::io::ErrorKind::InvalidData
+            && source.get_ref() == Some(&AUTHENTICATION_CODE_MISMATCH_MESSAGE)
+        {
+            return ZipReadError::AuthenticationCodeMismatch;
+        }
         ZipReadError::IOError { 0: source }
     }
 }
@@ -69,6 +101,30 @@ impl ::core::fmt::Debug for ZipReadError {
                 );
                 ::core::fmt::DebugStruct::finish(debug_trait_builder)
             }
+            (&ZipReadError::Crc32Mismatch {
+                expected: ref __self_0,
+                actual: ref __self_1,
+            },) => {
+                let debug_trait_builder =
+                    &mut ::core::fmt::Formatter::debug_struct(f, "Crc32Mismatch");
+                let _ = ::core::fmt::DebugStruct::field(
+                    debug_trait_builder,
+                    "expected",
+                    &&(*__self_0),
+                );
+                let _ = ::core::fmt::DebugStruct::field(
+                    debug_trait_builder,
+                    "actual",
+                    &&(*__self_1),
+                );
+                ::core::fmt::DebugStruct::finish(debug_trait_builder)
+            }
+            (&ZipReadError::InvalidPassword,) => {
+                ::core::fmt::Formatter::write_str(f, "InvalidPassword")
+            }
+            (&ZipReadError::AuthenticationCodeMismatch,) => {
+                ::core::fmt::Formatter::write_str(f, "AuthenticationCodeMismatch")
+            }
         }
     }
 }
\ No newline at end of file
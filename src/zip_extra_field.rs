@@ -0,0 +1,595 @@
+use super::zip_error::ZipReadError;
+use byteorder::{ReadBytesExt, WriteBytesExt, LE};
+
+use core2::io::{Cursor, Read};
+use core::convert::TryFrom;
+use alloc::vec;
+use alloc::vec::Vec;
+use alloc::format;
+use alloc::string::ToString;
+
+/// Header ID of ZIP64 extended information extra field
+pub const ZIP64_EXTRA_FIELD_ID: u16 = 0x0001;
+/// Header ID of NTFS extra field
+pub const NTFS_EXTRA_FIELD_ID: u16 = 0x000a;
+/// Header ID of extended timestamp extra field
+pub const EXTENDED_TIMESTAMP_EXTRA_FIELD_ID: u16 = 0x5455;
+/// Header ID of Info-ZIP Unix extra field (3rd generation)
+pub const INFO_ZIP_UNIX_EXTRA_FIELD_ID: u16 = 0x7875;
+/// Header ID of Info-ZIP Unicode path extra field
+pub const UNICODE_PATH_EXTRA_FIELD_ID: u16 = 0x7075;
+/// Header ID of WinZip AES extra field
+pub const AES_EXTRA_FIELD_ID: u16 = 0x9901;
+/// Minimum "version needed to extract" of ZIP64 entries and records (4.5)
+pub const ZIP64_VERSION_REQUIRED_TO_EXTRACT: u16 = 45;
+
+/// Iterator over the records (header ID and data) in an extra field
+///
+/// Iteration stops at the first truncated record.
+pub struct ZipExtraFieldIterator<'a> {
+    /// Byte sequence of extra field
+    extra_field: &'a [u8],
+    /// Position of the next record
+    pos: usize,
+}
+
+impl<'a> ZipExtraFieldIterator<'a> {
+    /// Generates an iterator over the records in `extra_field`
+    ///
+    /// # Arguments
+    ///
+    /// * `extra_field` - byte sequence of extra field
+    pub fn new(extra_field: &'a [u8]) -> Self {
+        return Self { extra_field, pos: 0 };
+    }
+
+    /// Returns `true` if the extra field ends in the middle of a record
+    ///
+    /// Meaningful only after the iteration has finished.
+    pub fn is_truncated(&self) -> bool {
+        return self.pos < self.extra_field.len();
+    }
+}
+
+impl<'a> Iterator for ZipExtraFieldIterator<'a> {
+    type Item = (u16, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let extra_field = self.extra_field;
+        let pos = self.pos;
+        if pos + 4 > extra_field.len() {
+            return None;
+        }
+        let header_id = u16::from_le_bytes([extra_field[pos], extra_field[pos + 1]]);
+        let data_size = u16::from_le_bytes([extra_field[pos + 2], extra_field[pos + 3]]) as usize;
+        let data_start = pos + 4;
+        let data_end = data_start + data_size;
+        if data_end > extra_field.len() {
+            return None;
+        }
+        self.pos = data_end;
+        return Some((header_id, &extra_field[data_start..data_end]));
+    }
+}
+
+/// Returns the data of the first extra field record whose header ID is `header_id`
+///
+/// Returns `None` if no such record exists or the extra field is truncated before it.
+///
+/// # Arguments
+///
+/// * `extra_field` - byte sequence of extra field
+/// * `header_id` - header ID to look for
+pub fn find_extra_field(extra_field: &[u8], header_id: u16) -> Option<&[u8]> {
+    return ZipExtraFieldIterator::new(extra_field)
+        .find(|(current_id, _)| *current_id == header_id)
+        .map(|(_, data)| data);
+}
+
+/// Returns the length of `extra_field` to store in the 16-bit "extra field length" field
+///
+/// # Errors
+///
+/// Returns an error of `ErrorKind::InvalidInput` if `extra_field` is longer than 65535 bytes.
+pub(crate) fn extra_field_length(extra_field: &[u8]) -> core2::io::Result<u16> {
+    return u16::try_from(extra_field.len()).map_err(|_| {
+        core2::io::Error::new(
+            core2::io::ErrorKind::InvalidInput,
+            "extra field must not be longer than 65535 bytes",
+        )
+    });
+}
+
+/// Removes all the extra field records whose header ID is `header_id` and appends a new one if `data` is given
+///
+/// Returns the new length of `extra_field`.
+///
+/// # Arguments
+///
+/// * `extra_field` - byte sequence of extra field
+/// * `header_id` - header ID of the record to replace
+/// * `data` - data of the new record (without header ID and data size)
+///
+/// # Errors
+///
+/// Returns an error of `ErrorKind::InvalidInput` and leaves `extra_field` unchanged if the result is longer than 65535 bytes.
+pub(crate) fn replace_extra_field(
+    extra_field: &mut Vec<u8>,
+    header_id: u16,
+    data: Option<&[u8]>,
+) -> core2::io::Result<u16> {
+    let mut result: Vec<u8> = Vec::with_capacity(extra_field.len());
+    let mut pos: usize = 0;
+    while pos + 4 <= extra_field.len() {
+        let current_id = u16::from_le_bytes([extra_field[pos], extra_field[pos + 1]]);
+        let data_size = u16::from_le_bytes([extra_field[pos + 2], extra_field[pos + 3]]) as usize;
+        let data_end = core::cmp::min(pos + 4 + data_size, extra_field.len());
+        if current_id != header_id {
+            result.extend_from_slice(&extra_field[pos..data_end]);
+        }
+        pos = data_end;
+    }
+    // keep trailing garbage as is
+    result.extend_from_slice(&extra_field[pos..]);
+    if let Some(data) = data {
+        result.extend_from_slice(&header_id.to_le_bytes());
+        result.extend_from_slice(&(data.len() as u16).to_le_bytes());
+        result.extend_from_slice(data);
+    }
+    let length = extra_field_length(&result)?;
+    *extra_field = result;
+    return Ok(length);
+}
+
+/// ZIP64 extended information extra field (header ID: 0x0001)
+///
+/// See 4.5.3 in https://pkware.cachefly.net/webdocs/casestudies/APPNOTE.TXT
+///
+/// Each value exists only if the corresponding 32-bit (or 16-bit) field of the local file header or central directory is set to `0xFFFFFFFF` (or `0xFFFF`).
+pub struct Zip64ExtendedInformation {
+    /// 64-bit uncompressed size
+    pub uncompressed_size: Option<u64>,
+    /// 64-bit compressed size
+    pub compressed_size: Option<u64>,
+    /// 64-bit **absolute** 0-based position of the local header
+    pub local_header_position: Option<u64>,
+    /// 32-bit number of the disk where the file starts
+    pub disk_number_start: Option<u32>,
+}
+
+impl Zip64ExtendedInformation {
+    /// Parses the data of ZIP64 extended information extra field (without header ID and data size).
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - data of the extra field record
+    /// * `has_uncompressed_size` - whether the uncompressed size field is `0xFFFFFFFF`
+    /// * `has_compressed_size` - whether the compressed size field is `0xFFFFFFFF`
+    /// * `has_local_header_position` - whether the local header position field is `0xFFFFFFFF`
+    /// * `has_disk_number_start` - whether the disk number start field is `0xFFFF`
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError::InvalidZipArchive` if `data` is too short.
+    pub fn from_bytes(
+        data: &[u8],
+        has_uncompressed_size: bool,
+        has_compressed_size: bool,
+        has_local_header_position: bool,
+        has_disk_number_start: bool,
+    ) -> Result<Self, ZipReadError> {
+        let mut read = Cursor::new(data);
+        let mut result = Self {
+            uncompressed_size: None,
+            compressed_size: None,
+            local_header_position: None,
+            disk_number_start: None,
+        };
+        let too_short = |_| ZipReadError::InvalidZipArchive {
+            reason: "ZIP64 extended information extra field is too short".to_string(),
+        };
+        if has_uncompressed_size {
+            result.uncompressed_size = Some(read.read_u64::<LE>().map_err(too_short)?);
+        }
+        if has_compressed_size {
+            result.compressed_size = Some(read.read_u64::<LE>().map_err(too_short)?);
+        }
+        if has_local_header_position {
+            result.local_header_position = Some(read.read_u64::<LE>().map_err(too_short)?);
+        }
+        if has_disk_number_start {
+            result.disk_number_start = Some(read.read_u32::<LE>().map_err(too_short)?);
+        }
+        return Ok(result);
+    }
+
+    /// Returns `true` if no value is contained
+    pub fn is_empty(&self) -> bool {
+        return self.uncompressed_size.is_none()
+            && self.compressed_size.is_none()
+            && self.local_header_position.is_none()
+            && self.disk_number_start.is_none();
+    }
+
+    /// Serializes the data of ZIP64 extended information extra field (without header ID and data size).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut result: Vec<u8> = vec![];
+        // Writing to `Vec` never fails.
+        if let Some(uncompressed_size) = self.uncompressed_size {
+            result.write_u64::<LE>(uncompressed_size).unwrap();
+        }
+        if let Some(compressed_size) = self.compressed_size {
+            result.write_u64::<LE>(compressed_size).unwrap();
+        }
+        if let Some(local_header_position) = self.local_header_position {
+            result.write_u64::<LE>(local_header_position).unwrap();
+        }
+        if let Some(disk_number_start) = self.disk_number_start {
+            result.write_u32::<LE>(disk_number_start).unwrap();
+        }
+        return result;
+    }
+}
+
+/// NTFS extra field (header ID: 0x000a)
+///
+/// See 4.5.5 in https://pkware.cachefly.net/webdocs/casestudies/APPNOTE.TXT
+///
+/// Times are in Windows FILETIME (100 ns intervals since 1601-01-01 UTC).
+pub struct NtfsExtraField {
+    /// Last modification time
+    pub mtime: u64,
+    /// Last access time
+    pub atime: u64,
+    /// Creation time
+    pub ctime: u64,
+}
+
+impl NtfsExtraField {
+    /// Parses the data of NTFS extra field (without header ID and data size).
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError::InvalidZipArchive` if the attribute tag 0x0001 (timestamps) is missing or broken.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, ZipReadError> {
+        // 4 bytes are reserved
+        let mut pos: usize = 4;
+        while pos + 4 <= data.len() {
+            let tag = u16::from_le_bytes([data[pos], data[pos + 1]]);
+            let size = u16::from_le_bytes([data[pos + 2], data[pos + 3]]) as usize;
+            let attr_start = pos + 4;
+            if tag == 0x0001 && size == 24 && attr_start + size <= data.len() {
+                let mut read = Cursor::new(&data[attr_start..attr_start + size]);
+                return Ok(Self {
+                    mtime: read.read_u64::<LE>()?,
+                    atime: read.read_u64::<LE>()?,
+                    ctime: read.read_u64::<LE>()?,
+                });
+            }
+            pos = attr_start + size;
+        }
+        return Err(ZipReadError::InvalidZipArchive {
+            reason: "NTFS extra field does not contain timestamps".to_string(),
+        });
+    }
+
+    /// Serializes the data of NTFS extra field (without header ID and data size).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut result: Vec<u8> = vec![];
+        // Writing to `Vec` never fails.
+        result.write_u32::<LE>(0).unwrap();
+        result.write_u16::<LE>(0x0001).unwrap();
+        result.write_u16::<LE>(24).unwrap();
+        result.write_u64::<LE>(self.mtime).unwrap();
+        result.write_u64::<LE>(self.atime).unwrap();
+        result.write_u64::<LE>(self.ctime).unwrap();
+        return result;
+    }
+}
+
+/// Extended timestamp extra field (header ID: 0x5455)
+///
+/// See 4.6 in https://pkware.cachefly.net/webdocs/casestudies/APPNOTE.TXT and `proginfo/extrafld.txt` of Info-ZIP
+///
+/// Times are in Unix time.  Central directories usually contain only the modification time even if `flags` says otherwise.
+pub struct ExtendedTimestamp {
+    /// bit #0: modification time, bit #1: access time, bit #2: creation time
+    pub flags: u8,
+    /// Last modification time
+    pub mtime: Option<i32>,
+    /// Last access time
+    pub atime: Option<i32>,
+    /// Creation time
+    pub ctime: Option<i32>,
+}
+
+impl ExtendedTimestamp {
+    /// Parses the data of extended timestamp extra field (without header ID and data size).
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError::InvalidZipArchive` if `data` is empty.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, ZipReadError> {
+        let mut read = Cursor::new(data);
+        let flags = read.read_u8().map_err(|_| ZipReadError::InvalidZipArchive {
+            reason: "extended timestamp extra field is empty".to_string(),
+        })?;
+        let mut result = Self {
+            flags,
+            mtime: None,
+            atime: None,
+            ctime: None,
+        };
+        // Missing values are not errors because they are omitted in central directories.
+        if flags & 0x01 != 0 {
+            result.mtime = read.read_u32::<LE>().ok().map(|time| time as i32);
+        }
+        if flags & 0x02 != 0 {
+            result.atime = read.read_u32::<LE>().ok().map(|time| time as i32);
+        }
+        if flags & 0x04 != 0 {
+            result.ctime = read.read_u32::<LE>().ok().map(|time| time as i32);
+        }
+        return Ok(result);
+    }
+
+    /// Serializes the data of extended timestamp extra field (without header ID and data size).
+    ///
+    /// Only the existing times are written.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut result: Vec<u8> = vec![self.flags];
+        // Writing to `Vec` never fails.
+        for time in [self.mtime, self.atime, self.ctime].iter().flatten() {
+            result.write_u32::<LE>(*time as u32).unwrap();
+        }
+        return result;
+    }
+}
+
+/// Info-ZIP Unix extra field (3rd generation; header ID: 0x7875)
+///
+/// See `proginfo/extrafld.txt` of Info-ZIP
+pub struct InfoZipUnix {
+    /// Version of this extra field (currently 1)
+    pub version: u8,
+    /// User ID
+    pub uid: u64,
+    /// Group ID
+    pub gid: u64,
+}
+
+impl InfoZipUnix {
+    /// Parses the data of Info-ZIP Unix extra field (without header ID and data size).
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError::InvalidZipArchive` if `data` is broken, or UID/GID is longer than 8 bytes.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, ZipReadError> {
+        let mut read = Cursor::new(data);
+        let broken = |_| ZipReadError::InvalidZipArchive {
+            reason: "Info-ZIP Unix extra field is broken".to_string(),
+        };
+        let version = read.read_u8().map_err(broken)?;
+        let uid = read_variable_length_id(&mut read)?;
+        let gid = read_variable_length_id(&mut read)?;
+        return Ok(Self { version, uid, gid });
+    }
+
+    /// Serializes the data of Info-ZIP Unix extra field (without header ID and data size).
+    ///
+    /// UID and GID are written in 4 bytes unless they do not fit.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut result: Vec<u8> = vec![self.version];
+        for id in [self.uid, self.gid].iter() {
+            let size: usize = if *id > u32::MAX as u64 { 8 } else { 4 };
+            result.push(size as u8);
+            result.extend_from_slice(&id.to_le_bytes()[..size]);
+        }
+        return result;
+    }
+}
+
+/// Reads "size (1 byte) + little-endian value" used in Info-ZIP Unix extra field
+fn read_variable_length_id(read: &mut Cursor<&[u8]>) -> Result<u64, ZipReadError> {
+    let broken = |_| ZipReadError::InvalidZipArchive {
+        reason: "Info-ZIP Unix extra field is broken".to_string(),
+    };
+    let size = read.read_u8().map_err(broken)? as usize;
+    if size > 8 {
+        return Err(ZipReadError::UnsupportedZipArchive {
+            reason: format!("UID/GID of {} bytes is not supported", size),
+        });
+    }
+    let mut buf: [u8; 8] = [0; 8];
+    read.read_exact(&mut buf[..size]).map_err(broken)?;
+    return Ok(u64::from_le_bytes(buf));
+}
+
+/// Info-ZIP Unicode path extra field (header ID: 0x7075)
+///
+/// See 4.6.9 in https://pkware.cachefly.net/webdocs/casestudies/APPNOTE.TXT
+pub struct UnicodePath {
+    /// Version of this extra field (currently 1)
+    pub version: u8,
+    /// CRC-32 of the file name field in the header; the Unicode path is valid only if it matches
+    pub name_crc32: u32,
+    /// File name encoded in UTF-8
+    pub unicode_name: Vec<u8>,
+}
+
+impl UnicodePath {
+    /// Parses the data of Unicode path extra field (without header ID and data size).
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError::InvalidZipArchive` if `data` is shorter than 5 bytes.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, ZipReadError> {
+        if data.len() < 5 {
+            return Err(ZipReadError::InvalidZipArchive {
+                reason: "Unicode path extra field is too short".to_string(),
+            });
+        }
+        let mut read = Cursor::new(data);
+        return Ok(Self {
+            version: read.read_u8()?,
+            name_crc32: read.read_u32::<LE>()?,
+            unicode_name: data[5..].to_vec(),
+        });
+    }
+
+    /// Serializes the data of Unicode path extra field (without header ID and data size).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut result: Vec<u8> = vec![self.version];
+        result.extend_from_slice(&self.name_crc32.to_le_bytes());
+        result.extend_from_slice(&self.unicode_name);
+        return result;
+    }
+}
+
+/// WinZip AES extra field (header ID: 0x9901)
+///
+/// See https://www.winzip.com/en/support/aes-encryption/
+pub struct AesExtraField {
+    /// 1: AE-1, 2: AE-2
+    pub vendor_version: u16,
+    /// Always `b"AE"`
+    pub vendor_id: [u8; 2],
+    /// 1: AES-128, 2: AES-192, 3: AES-256
+    pub strength: u8,
+    /// Actual compression method of the file content
+    pub compression_method: u16,
+}
+
+impl AesExtraField {
+    /// Parses the data of WinZip AES extra field (without header ID and data size).
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError::InvalidZipArchive` if `data` is not 7 bytes long or the vendor ID is not "AE".
+    pub fn from_bytes(data: &[u8]) -> Result<Self, ZipReadError> {
+        if data.len() != 7 || &data[2..4] != b"AE" {
+            return Err(ZipReadError::InvalidZipArchive {
+                reason: "WinZip AES extra field is broken".to_string(),
+            });
+        }
+        let mut read = Cursor::new(data);
+        return Ok(Self {
+            vendor_version: read.read_u16::<LE>()?,
+            vendor_id: [read.read_u8()?, read.read_u8()?],
+            strength: read.read_u8()?,
+            compression_method: read.read_u16::<LE>()?,
+        });
+    }
+
+    /// Serializes the data of WinZip AES extra field (without header ID and data size).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut result: Vec<u8> = vec![];
+        result.extend_from_slice(&self.vendor_version.to_le_bytes());
+        result.extend_from_slice(&self.vendor_id);
+        result.push(self.strength);
+        result.extend_from_slice(&self.compression_method.to_le_bytes());
+        return result;
+    }
+}
+
+/// Builder of extra field byte sequences
+///
+/// Use `set_extra_field_from_slice` of `ZipCDEntry` or `ZipLocalFileHeader` to store the result
+/// so that `extra_field_length` is kept consistent.
+pub struct ZipExtraFieldBuilder {
+    /// Records (header ID and data) in order
+    records: Vec<(u16, Vec<u8>)>,
+}
+
+impl ZipExtraFieldBuilder {
+    /// Generates an empty builder
+    pub fn new() -> Self {
+        return Self { records: vec![] };
+    }
+
+    /// Generates a builder that contains the records in the existing extra field
+    ///
+    /// A truncated record at the end is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `extra_field` - byte sequence of extra field
+    pub fn from_extra_field(extra_field: &[u8]) -> Self {
+        return Self {
+            records: ZipExtraFieldIterator::new(extra_field)
+                .map(|(header_id, data)| (header_id, data.to_vec()))
+                .collect(),
+        };
+    }
+
+    /// Appends a record.  An existing record with the same header ID is removed.
+    ///
+    /// The length of `data` is checked by `build`.
+    ///
+    /// # Arguments
+    ///
+    /// * `header_id` - header ID of the record
+    /// * `data` - data of the record (without header ID and data size)
+    pub fn set(mut self, header_id: u16, data: Vec<u8>) -> Self {
+        self.records.retain(|(current_id, _)| *current_id != header_id);
+        self.records.push((header_id, data));
+        return self;
+    }
+
+    /// Removes the record whose header ID is `header_id`
+    ///
+    /// # Arguments
+    ///
+    /// * `header_id` - header ID of the record to remove
+    pub fn remove(mut self, header_id: u16) -> Self {
+        self.records.retain(|(current_id, _)| *current_id != header_id);
+        return self;
+    }
+
+    /// Sets ZIP64 extended information extra field
+    pub fn zip64(self, field: &Zip64ExtendedInformation) -> Self {
+        return self.set(ZIP64_EXTRA_FIELD_ID, field.to_bytes());
+    }
+
+    /// Sets NTFS extra field
+    pub fn ntfs(self, field: &NtfsExtraField) -> Self {
+        return self.set(NTFS_EXTRA_FIELD_ID, field.to_bytes());
+    }
+
+    /// Sets extended timestamp extra field
+    pub fn extended_timestamp(self, field: &ExtendedTimestamp) -> Self {
+        return self.set(EXTENDED_TIMESTAMP_EXTRA_FIELD_ID, field.to_bytes());
+    }
+
+    /// Sets Info-ZIP Unix extra field
+    pub fn info_zip_unix(self, field: &InfoZipUnix) -> Self {
+        return self.set(INFO_ZIP_UNIX_EXTRA_FIELD_ID, field.to_bytes());
+    }
+
+    /// Sets Info-ZIP Unicode path extra field
+    pub fn unicode_path(self, field: &UnicodePath) -> Self {
+        return self.set(UNICODE_PATH_EXTRA_FIELD_ID, field.to_bytes());
+    }
+
+    /// Sets WinZip AES extra field
+    pub fn aes(self, field: &AesExtraField) -> Self {
+        return self.set(AES_EXTRA_FIELD_ID, field.to_bytes());
+    }
+
+    /// Serializes all the records
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of `ErrorKind::InvalidInput` if the result is longer than 65535 bytes
+    /// (which includes the case that the data of a record is longer than 65535 bytes).
+    pub fn build(&self) -> core2::io::Result<Vec<u8>> {
+        let mut result: Vec<u8> = vec![];
+        for (header_id, data) in &self.records {
+            result.extend_from_slice(&header_id.to_le_bytes());
+            result.extend_from_slice(&(data.len() as u16).to_le_bytes());
+            result.extend_from_slice(data);
+        }
+        extra_field_length(&result)?;
+        return Ok(result);
+    }
+}
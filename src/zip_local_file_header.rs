@@ -1,5 +1,17 @@
-use super::zip_central_directory::{ZipCDEntry, DATA_DESCRIPTOR_EXISTS_FLAG_BIT, UTF8_FLAG_BIT};
+use super::zip_central_directory::{
+    ZipCDEntry, ZipCDEntryRef, DATA_DESCRIPTOR_EXISTS_FLAG_BIT, DATA_ENCRYPTED_FLAG_BIT, UTF8_FLAG_BIT,
+};
+use super::zip_compression::{
+    inflate_to_end, is_supported_compression_method, ZipDecompressReader, COMPRESSION_METHOD_DEFLATE,
+};
+use super::zip_crypto::{
+    password_check_byte, ZipDecryptReader, AES_AUTHENTICATION_CODE_SIZE, COMPRESSION_METHOD_AES,
+};
 use super::zip_error::ZipReadError;
+use super::zip_extra_field::{
+    extra_field_length, find_extra_field, ZipExtraFieldIterator, replace_extra_field, AesExtraField, Zip64ExtendedInformation,
+    AES_EXTRA_FIELD_ID, ZIP64_EXTRA_FIELD_ID, ZIP64_VERSION_REQUIRED_TO_EXTRACT,
+};
 use byteorder::{ReadBytesExt, WriteBytesExt, LE};
 
 use alloc::borrow::Cow;
@@ -8,26 +20,40 @@ use alloc::vec;
 use alloc::vec::Vec;
 use alloc::format;
 
-use core2::io::Read;
+use core2::io::{BufRead, Read, Take};
 
 #[cfg(not(feature = "std"))]
 use crate::read_ext::ReadExt;
+use crate::read_ext::{slice_from, take_slice, BufReader};
 
 /// magick number of local file header
-const LOCAL_FILE_MAGIC: [u8; 4] = [0x50, 0x4b, 0x3, 0x4];
+pub(crate) const LOCAL_FILE_MAGIC: [u8; 4] = [0x50, 0x4b, 0x3, 0x4];
+/// magick number of data descriptor (optional)
+const DATA_DESCRIPTOR_MAGIC: [u8; 4] = [0x50, 0x4b, 0x7, 0x8];
+/// Maximum number of bytes the deflate decoder reads beyond the end of the stream
+const MAX_DEFLATE_LOOKAHEAD: usize = 4;
+/// Size of the buffer to decode data before a candidate of the data descriptor
+const DECODE_BUFFER_SIZE: usize = 4096;
 
 /// Class for Data Descriptor
 ///
 /// Used when bit #3 of general purpose bit of lcoal header or central directory is set
+///
+/// Generate it with `ZipDataDescriptor::new`; fields may be added in the future.
+#[non_exhaustive]
 pub struct ZipDataDescriptor {
     /// See 4.4.7 in https://pkware.cachefly.net/webdocs/casestudies/APPNOTE.TXT
     ///
     /// Unaffected by file renaming
     pub crc32: u32,
     /// As the name implies.  Note that the file name is not included.
-    pub compressed_size: u32,
+    pub compressed_size: u64,
     /// As the name implies.  Note that the file name is not included.
-    pub uncompressed_size: u32,
+    pub uncompressed_size: u64,
+    /// `true` if preceded by the optional signature (PK\x07\x08)
+    pub has_signature: bool,
+    /// `true` if the sizes are 8 bytes (ZIP64; 4.3.9.2 in https://pkware.cachefly.net/webdocs/casestudies/APPNOTE.TXT)
+    pub is_zip64: bool,
 }
 
 impl ZipDataDescriptor {
@@ -36,21 +62,431 @@ impl ZipDataDescriptor {
             crc32: 0,
             compressed_size: 0,
             uncompressed_size: 0,
+            has_signature: false,
+            is_zip64: false,
+        };
+    }
+    /// Generates a data descriptor
+    ///
+    /// The ZIP64 format is used if either size does not fit in 32 bits;
+    /// the local file header must then have ZIP64 extended information extra field.
+    ///
+    /// # Arguments
+    ///
+    /// * `crc32` - CRC-32 of the file content
+    /// * `compressed_size` - compressed size
+    /// * `uncompressed_size` - uncompressed size
+    /// * `has_signature` - `true` to write the optional signature (PK\x07\x08)
+    pub fn new(crc32: u32, compressed_size: u64, uncompressed_size: u64, has_signature: bool) -> Self {
+        return Self {
+            crc32,
+            compressed_size,
+            uncompressed_size,
+            has_signature,
+            is_zip64: compressed_size >= u32::MAX as u64 || uncompressed_size >= u32::MAX as u64,
         };
     }
-    fn from_reader<T: ReadBytesExt>(read: &mut T) -> Result<Self, ZipReadError> {
+    /// Reads a data descriptor with or without the optional signature
+    ///
+    /// If the first 4 bytes are PK\x07\x08, they are regarded as the signature.
+    ///
+    /// # Arguments
+    ///
+    /// * `read` - `Read` object (must be at the end of the file content)
+    /// * `is_zip64` - `true` if the local file header has ZIP64 extended information extra field
+    fn from_reader<T: ReadBytesExt>(read: &mut T, is_zip64: bool) -> Result<Self, ZipReadError> {
         let mut result = Self::empty();
-        result.crc32 = read.read_u32::<LE>()?;
-        result.compressed_size = read.read_u32::<LE>()?;
-        result.uncompressed_size = read.read_u32::<LE>()?;
+        result.is_zip64 = is_zip64;
+        let mut signature_candidate: [u8; 4] = [0; 4];
+        read.read_exact(&mut signature_candidate)?;
+        if signature_candidate == DATA_DESCRIPTOR_MAGIC {
+            result.has_signature = true;
+            result.crc32 = read.read_u32::<LE>()?;
+        } else {
+            result.crc32 = u32::from_le_bytes(signature_candidate);
+        }
+        if is_zip64 {
+            result.compressed_size = read.read_u64::<LE>()?;
+            result.uncompressed_size = read.read_u64::<LE>()?;
+        } else {
+            result.compressed_size = read.read_u32::<LE>()? as u64;
+            result.uncompressed_size = read.read_u32::<LE>()? as u64;
+        }
         return Ok(result);
     }
-    fn write<T: WriteBytesExt>(&self, write: &mut T) -> core2::io::Result<u64> {
+    /// Returns the size of this data descriptor in bytes
+    pub fn size(&self) -> u64 {
+        let sizes_length = if self.is_zip64 { 16 } else { 8 };
+        let signature_length = if self.has_signature {
+            DATA_DESCRIPTOR_MAGIC.len() as u64
+        } else {
+            0
+        };
+        return signature_length + 4 + sizes_length;
+    }
+    /// Checks that CRC-32 and sizes equal those in the central directory
+    ///
+    /// # Arguments
+    ///
+    /// * `cd` - central directory entry of the file
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError::InvalidZipArchive` if any of them differs.
+    pub fn check_against_central_directory(&self, cd: &ZipCDEntry) -> Result<(), ZipReadError> {
+        return check_data_descriptor(
+            self,
+            cd.crc32,
+            cd.effective_compressed_size(),
+            cd.effective_uncompressed_size(),
+        );
+    }
+    /// Writes the data descriptor (with the signature if `has_signature`) and returns the number of bytes written.
+    ///
+    /// # Arguments
+    ///
+    /// * `write` - file handler
+    pub fn write<T: WriteBytesExt>(&self, write: &mut T) -> core2::io::Result<u64> {
+        if self.has_signature {
+            write.write_all(&DATA_DESCRIPTOR_MAGIC)?;
+        }
         write.write_u32::<LE>(self.crc32)?;
-        write.write_u32::<LE>(self.compressed_size)?;
-        write.write_u32::<LE>(self.uncompressed_size)?;
-        return Ok(12);
+        if self.is_zip64 {
+            write.write_u64::<LE>(self.compressed_size)?;
+            write.write_u64::<LE>(self.uncompressed_size)?;
+        } else {
+            write.write_u32::<LE>(self.compressed_size as u32)?;
+            write.write_u32::<LE>(self.uncompressed_size as u32)?;
+        }
+        return Ok(self.size());
+    }
+}
+
+/// Checks that CRC-32 and sizes in a data descriptor equal the given ones (from the central directory)
+fn check_data_descriptor(
+    data_descriptor: &ZipDataDescriptor,
+    crc32: u32,
+    compressed_size: u64,
+    uncompressed_size: u64,
+) -> Result<(), ZipReadError> {
+    if data_descriptor.crc32 != crc32
+        || data_descriptor.compressed_size != compressed_size
+        || data_descriptor.uncompressed_size != uncompressed_size
+    {
+        return Err(ZipReadError::InvalidZipArchive {
+            reason: format!(
+                "data descriptor (CRC-32: {:08x} / compressed size: {} / uncompressed size: {}) differs from central directory (CRC-32: {:08x} / compressed size: {} / uncompressed size: {})",
+                data_descriptor.crc32,
+                data_descriptor.compressed_size,
+                data_descriptor.uncompressed_size,
+                crc32,
+                compressed_size,
+                uncompressed_size
+            ),
+        });
+    }
+    return Ok(());
+}
+
+/// Reads file content whose sizes are unknown and the data descriptor just after it
+///
+/// The end of deflate data is located by decoding it (see `read_deflate_data_and_data_descriptor`).
+/// For the other compression methods, the data descriptor is searched (see `search_data_descriptor`)
+/// and accepted only if the data before it is decoded into the CRC-32 and size it records.
+/// Encrypted data and data of unsupported compression methods cannot be decoded,
+/// so the first data descriptor whose compressed size matches is accepted.
+/// `read` is left just after the data descriptor.
+///
+/// # Arguments
+///
+/// * `read` - `BufRead` object (must be at the start of the file content)
+/// * `compression_method` - compression method of the file content
+/// * `general_purpose_flags` - general purpose bit flag of the local file header
+/// * `is_zip64` - `true` if the local file header has ZIP64 extended information extra field
+///
+/// # Errors
+///
+/// Returns `ZipReadError::InvalidZipArchive` if no matching data descriptor is found.
+fn read_data_and_data_descriptor<T: BufRead>(
+    read: &mut T,
+    compression_method: u16,
+    general_purpose_flags: u16,
+    is_zip64: bool,
+) -> Result<(Vec<u8>, ZipDataDescriptor), ZipReadError> {
+    if (DATA_ENCRYPTED_FLAG_BIT & general_purpose_flags) != 0
+        || !is_supported_compression_method(compression_method)
+    {
+        return search_data_descriptor(read, is_zip64, |_, _| true);
+    }
+    if compression_method == COMPRESSION_METHOD_DEFLATE {
+        return read_deflate_data_and_data_descriptor(read, is_zip64);
+    }
+    return search_data_descriptor(read, is_zip64, |data, data_descriptor| {
+        decodes_to(data, compression_method, general_purpose_flags, data_descriptor)
+    });
+}
+
+/// Reads deflate data whose size is unknown and the data descriptor just after it
+///
+/// The end of the data is located by decoding it; then the data descriptor is searched among the few bytes
+/// the decoder may have read ahead, and accepted only if its CRC-32 and sizes match the decoded data.
+/// `read` is left just after the data descriptor.
+///
+/// # Arguments
+///
+/// * `read` - `BufRead` object (must be at the start of the file content)
+/// * `is_zip64` - `true` if the local file header has ZIP64 extended information extra field
+///
+/// # Errors
+///
+/// Returns `ZipReadError::InvalidZipArchive` if no matching data descriptor is found.
+fn read_deflate_data_and_data_descriptor<T: BufRead>(
+    read: &mut T,
+    is_zip64: bool,
+) -> Result<(Vec<u8>, ZipDataDescriptor), ZipReadError> {
+    let stream = inflate_to_end(read)?;
+    let mut compressed_data = stream.consumed;
+    // The bytes the decoder may have read ahead, followed by the data descriptor as it is read
+    let lookahead_start = compressed_data.len().saturating_sub(MAX_DEFLATE_LOOKAHEAD);
+    let mut tail = compressed_data.split_off(lookahead_start);
+    let sizes_length = if is_zip64 { 16 } else { 8 };
+    // (end of the data in `tail`, whether the data descriptor has the signature), in the order of the end of the data descriptor
+    let mut candidates: Vec<(usize, bool)> = vec![];
+    for data_end in 0..=tail.len() {
+        candidates.push((data_end, false));
+        candidates.push((data_end, true));
+    }
+    let descriptor_length = |has_signature: bool| -> usize {
+        let signature_length = if has_signature {
+            DATA_DESCRIPTOR_MAGIC.len()
+        } else {
+            0
+        };
+        return signature_length + 4 + sizes_length;
+    };
+    candidates.sort_by_key(|&(data_end, has_signature)| data_end + descriptor_length(has_signature));
+    for (data_end, has_signature) in candidates {
+        let descriptor_end = data_end + descriptor_length(has_signature);
+        if tail.len() > descriptor_end {
+            continue;
+        }
+        // Reads only as far as this candidate needs, so that `read` is not advanced beyond the data descriptor
+        while tail.len() < descriptor_end {
+            tail.push(read.read_u8()?);
+        }
+        if tail[data_end..].starts_with(&DATA_DESCRIPTOR_MAGIC) != has_signature {
+            continue;
+        }
+        let data_descriptor =
+            ZipDataDescriptor::from_reader(&mut &tail[data_end..descriptor_end], is_zip64)?;
+        if data_descriptor.crc32 == stream.crc32
+            && data_descriptor.compressed_size == (lookahead_start + data_end) as u64
+            && data_descriptor.uncompressed_size == stream.uncompressed_size
+        {
+            compressed_data.extend_from_slice(&tail[..data_end]);
+            return Ok((compressed_data, data_descriptor));
+        }
+    }
+    return Err(ZipReadError::InvalidZipArchive {
+        reason: "data descriptor was not found after the deflate data".into(),
+    });
+}
+
+/// Reads file content whose size is unknown and the data descriptor just after it by searching the data descriptor
+///
+/// Every position followed by a data descriptor (with or without the signature) whose compressed size equals
+/// the length of the data before it is a candidate.  Candidates are examined in the order of the end of the data descriptor,
+/// so `read` is consumed only up to the end of the accepted one.
+///
+/// # Arguments
+///
+/// * `read` - `BufRead` object (must be at the start of the file content)
+/// * `is_zip64` - `true` if the local file header has ZIP64 extended information extra field
+/// * `verify` - returns `true` if the data before a candidate matches the data descriptor
+///
+/// # Errors
+///
+/// Returns `ZipReadError::InvalidZipArchive` if `read` ends before an accepted data descriptor.
+fn search_data_descriptor<T, F>(
+    read: &mut T,
+    is_zip64: bool,
+    mut verify: F,
+) -> Result<(Vec<u8>, ZipDataDescriptor), ZipReadError>
+where
+    T: BufRead,
+    F: FnMut(&[u8], &ZipDataDescriptor) -> bool,
+{
+    let size_length: usize = if is_zip64 { 8 } else { 4 };
+    let mut bytes: Vec<u8> = vec![];
+    loop {
+        let available = read.fill_buf()?;
+        if available.is_empty() {
+            return Err(ZipReadError::InvalidZipArchive {
+                reason: "data descriptor was not found after the file content".into(),
+            });
+        }
+        let mut n_consumed: usize = 0;
+        let mut accepted: Option<(usize, ZipDataDescriptor)> = None;
+        for &byte in available {
+            bytes.push(byte);
+            n_consumed += 1;
+            for has_signature in [false, true] {
+                let signature_length = if has_signature {
+                    DATA_DESCRIPTOR_MAGIC.len()
+                } else {
+                    0
+                };
+                let descriptor_length = signature_length + 4 + 2 * size_length;
+                if bytes.len() < descriptor_length {
+                    continue;
+                }
+                let data_end = bytes.len() - descriptor_length;
+                let descriptor = &bytes[data_end..];
+                if descriptor.starts_with(&DATA_DESCRIPTOR_MAGIC) != has_signature {
+                    continue;
+                }
+                // The compressed size is compared first, since parsing at every position is costly
+                let mut compressed_size: [u8; 8] = [0; 8];
+                compressed_size[..size_length]
+                    .copy_from_slice(&descriptor[signature_length + 4..signature_length + 4 + size_length]);
+                if u64::from_le_bytes(compressed_size) != data_end as u64 {
+                    continue;
+                }
+                let data_descriptor = ZipDataDescriptor::from_reader(&mut &descriptor[..], is_zip64)?;
+                if verify(&bytes[..data_end], &data_descriptor) {
+                    accepted = Some((data_end, data_descriptor));
+                    break;
+                }
+            }
+            if accepted.is_some() {
+                break;
+            }
+        }
+        read.consume(n_consumed);
+        if let Some((data_end, data_descriptor)) = accepted {
+            bytes.truncate(data_end);
+            return Ok((bytes, data_descriptor));
+        }
+    }
+}
+
+/// Returns `true` if `data` is decoded into the data whose CRC-32 and size are recorded in `data_descriptor`
+///
+/// # Arguments
+///
+/// * `data` - compressed data
+/// * `compression_method` - compression method of `data`
+/// * `general_purpose_flags` - general purpose bit flag of the local file header
+/// * `data_descriptor` - data descriptor after `data`
+fn decodes_to(
+    data: &[u8],
+    compression_method: u16,
+    general_purpose_flags: u16,
+    data_descriptor: &ZipDataDescriptor,
+) -> bool {
+    let mut reader = match ZipDecompressReader::new(data, compression_method) {
+        Ok(reader) => reader
+            .with_entry_info(general_purpose_flags, data_descriptor.uncompressed_size)
+            .with_expected_crc32(data_descriptor.crc32),
+        Err(_) => return false,
+    };
+    let mut buf: Vec<u8> = vec![0; DECODE_BUFFER_SIZE];
+    let mut uncompressed_size: u64 = 0;
+    loop {
+        match reader.read_decompressed(&mut buf) {
+            Ok(0) => return uncompressed_size == data_descriptor.uncompressed_size,
+            Ok(n_read) => {
+                uncompressed_size += n_read as u64;
+                if uncompressed_size > data_descriptor.uncompressed_size {
+                    return false;
+                }
+            }
+            Err(_) => return false,
+        }
+    }
+}
+
+/// Returns `ZipReadError::UnsupportedZipArchive` if the file content is encrypted
+fn check_not_encrypted(general_purpose_flags: u16) -> Result<(), ZipReadError> {
+    if (DATA_ENCRYPTED_FLAG_BIT & general_purpose_flags) != 0 {
+        return Err(ZipReadError::UnsupportedZipArchive {
+            reason: "the file content is encrypted; a password is required".into(),
+        });
+    }
+    return Ok(());
+}
+
+/// Returns a `Read` object that decrypts (if encrypted) and decompresses the file content and verifies its CRC-32 at the end
+///
+/// For WinZip AES (compression method 99), the actual compression method is taken from WinZip AES extra field,
+/// the authentication code at the end of `data` is verified, and CRC-32 is verified only for AE-1.
+///
+/// # Arguments
+///
+/// * `data` - compressed (and encrypted) file content
+/// * `general_purpose_flags` - general purpose bit flag of the local file header
+/// * `compression_method` - compression method of the local file header
+/// * `extra_field` - extra field of the local file header
+/// * `check_byte` - see `password_check_byte` (used for the traditional PKWARE encryption)
+/// * `expected_crc32` - CRC-32 recorded in the data descriptor (if exists) or the local file header
+/// * `uncompressed_size` - uncompressed size recorded in the data descriptor (if exists) or the local file header
+/// * `password` - byte sequence of the password (ignored if the file content is not encrypted)
+#[allow(clippy::too_many_arguments)]
+fn decrypted_data_reader<'d>(
+    data: &'d [u8],
+    general_purpose_flags: u16,
+    compression_method: u16,
+    extra_field: &[u8],
+    check_byte: u8,
+    expected_crc32: u32,
+    uncompressed_size: u64,
+    password: &[u8],
+) -> Result<ZipDecompressReader<ZipDecryptReader<&'d [u8]>>, ZipReadError> {
+    if (DATA_ENCRYPTED_FLAG_BIT & general_purpose_flags) == 0 {
+        return Ok(
+            ZipDecompressReader::new(ZipDecryptReader::plain(data), compression_method)?
+                .with_entry_info(general_purpose_flags, uncompressed_size)
+                .with_expected_crc32(expected_crc32),
+        );
+    }
+    if compression_method != COMPRESSION_METHOD_AES {
+        let decrypted_data = ZipDecryptReader::zip_crypto(data, password, check_byte)?;
+        return Ok(
+            ZipDecompressReader::new(decrypted_data, compression_method)?
+                .with_entry_info(general_purpose_flags, uncompressed_size)
+                .with_expected_crc32(expected_crc32),
+        );
     }
+    let aes_extra_field = match find_extra_field(extra_field, AES_EXTRA_FIELD_ID) {
+        Some(field) => AesExtraField::from_bytes(field)?,
+        None => {
+            return Err(ZipReadError::InvalidZipArchive {
+                reason: "WinZip AES extra field is missing".into(),
+            })
+        }
+    };
+    if data.len() < AES_AUTHENTICATION_CODE_SIZE as usize {
+        return Err(ZipReadError::InvalidZipArchive {
+            reason: "WinZip AES encrypted data is too short".into(),
+        });
+    }
+    let (encrypted_data, authentication_code) =
+        data.split_at(data.len() - AES_AUTHENTICATION_CODE_SIZE as usize);
+    let mut expected_authentication_code = [0; AES_AUTHENTICATION_CODE_SIZE as usize];
+    expected_authentication_code.copy_from_slice(authentication_code);
+    let decrypted_data = ZipDecryptReader::aes(
+        encrypted_data,
+        password,
+        aes_extra_field.strength,
+        expected_authentication_code,
+    )?;
+    let result = ZipDecompressReader::new(decrypted_data, aes_extra_field.compression_method)?
+        .with_entry_info(general_purpose_flags, uncompressed_size);
+    // CRC-32 is 0 in AE-2 to hide the information of the file content
+    if aes_extra_field.vendor_version == 1 {
+        return Ok(result.with_expected_crc32(expected_crc32));
+    }
+    return Ok(result);
 }
 
 /// An entry of local header of ZIP file
@@ -142,7 +578,68 @@ impl ZipLocalFileHeader<'_> {
         &mut self,
         read: &mut T,
     ) -> Result<(), ZipReadError> {
-        self.starting_position_without_signature = read.seek(SeekFrom::Current(0))?;
+        let starting_position_without_signature = read.seek(SeekFrom::Current(0))?;
+        self.read_header_without_signature(read, starting_position_without_signature)?;
+        if self.has_unknown_sizes() {
+            // Bytes read ahead while locating the end of the file content are given back by seeking
+            let mut buffered = BufReader::new(&mut *read);
+            self.read_data_of_unknown_size(&mut buffered)?;
+            let n_read_ahead = buffered.buffer().len() as i64;
+            read.seek(SeekFrom::Current(-n_read_ahead))?;
+            return Ok(());
+        }
+        return self.read_data(read, self.effective_compressed_size());
+    }
+
+    /// Returns `true` if bit #3 of general purpose flag is set and the compressed size is 0,
+    /// i.e. the sizes are only in the data descriptor after the file content
+    fn has_unknown_sizes(&self) -> bool {
+        return self.has_data_descriptor_by_flag() && self.effective_compressed_size() == 0;
+    }
+
+    /// Reads the file content and the data descriptor just after the header.
+    ///
+    /// If the sizes are unknown (see `has_unknown_sizes`), the end of the content is located by decoding it.
+    ///
+    /// # Arguments
+    /// * `read` - `BufRead` object (must be at the start of the file content)
+    fn read_data_or_decode<T: BufRead>(&mut self, read: &mut T) -> Result<(), ZipReadError> {
+        if self.has_unknown_sizes() {
+            return self.read_data_of_unknown_size(read);
+        }
+        return self.read_data(read, self.effective_compressed_size());
+    }
+
+    /// Reads the file content whose sizes are unknown and the data descriptor just after it
+    ///
+    /// # Arguments
+    /// * `read` - `BufRead` object (must be at the start of the file content)
+    fn read_data_of_unknown_size<T: BufRead>(&mut self, read: &mut T) -> Result<(), ZipReadError> {
+        let is_zip64 = self.zip64_extended_information()?.is_some();
+        let (compressed_data, data_descriptor) = read_data_and_data_descriptor(
+            read,
+            self.compression_method,
+            self.general_purpose_flags,
+            is_zip64,
+        )?;
+        self.compressed_data = compressed_data.into();
+        self.data_descriptor = Some(data_descriptor);
+        return Ok(());
+    }
+
+    /// Reads the fixed fields, file name and extra field from next to the signature (magick number) of the local file header.
+    ///
+    /// The file content is not read; `read` is left at the start of it.
+    ///
+    /// # Arguments
+    /// * `read` - `Read` object (must be at the next to the signature)
+    /// * `starting_position_without_signature` - the position of `read`
+    fn read_header_without_signature<T: ReadBytesExt>(
+        &mut self,
+        read: &mut T,
+        starting_position_without_signature: u64,
+    ) -> Result<(), ZipReadError> {
+        self.starting_position_without_signature = starting_position_without_signature;
         self.starting_position_with_signature =
             self.starting_position_without_signature - LOCAL_FILE_MAGIC.len() as u64;
         self.version_required_to_extract = read.read_u16::<LE>()?;
@@ -177,21 +674,36 @@ impl ZipLocalFileHeader<'_> {
                 ),
             });
         }
+        self.zip64_extended_information()?;
+        return Ok(());
+    }
+
+    /// Reads the file content (and the data descriptor if exists) just after the header.
+    ///
+    /// # Arguments
+    /// * `read` - `Read` object (must be at the start of the file content)
+    /// * `compressed_size` - size of the file content
+    fn read_data<T: ReadBytesExt>(
+        &mut self,
+        read: &mut T,
+        compressed_size: u64,
+    ) -> Result<(), ZipReadError> {
         let mut data_buf: Vec<u8> = Default::default();
         let read_compressed_size = read
-            .take(self.compressed_size as u64)
+            .take(compressed_size)
             .read_to_end(&mut data_buf)?;
-        if read_compressed_size != self.compressed_size as usize {
+        if read_compressed_size as u64 != compressed_size {
             return Err(ZipReadError::InvalidZipArchive {
                 reason: format!(
                     "compressed size is invalid (expected from length value field: {} / got {}",
-                    self.compressed_size, read_compressed_size
+                    compressed_size, read_compressed_size
                 ),
             });
         }
         self.compressed_data = data_buf.into();
         if self.has_data_descriptor_by_flag() {
-            self.data_descriptor = Some(ZipDataDescriptor::from_reader(read)?);
+            let is_zip64 = self.zip64_extended_information()?.is_some();
+            self.data_descriptor = Some(ZipDataDescriptor::from_reader(read, is_zip64)?);
         }
         return Ok(());
     }
@@ -211,6 +723,113 @@ impl ZipLocalFileHeader<'_> {
         self.file_name_raw.clone_from(name);
     }
 
+    /// Replaces the extra field.
+    ///
+    /// # Arguments
+    ///
+    /// * `extra_field` - Slice of new extra field (e.g. built by `ZipExtraFieldBuilder`)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of `ErrorKind::InvalidInput` if `extra_field` is longer than 65535 bytes.
+    pub fn set_extra_field_from_slice(&mut self, extra_field: &Vec<u8>) -> core2::io::Result<()> {
+        self.extra_field_length = extra_field_length(extra_field)?;
+        self.extra_field.clone_from(extra_field);
+        return Ok(());
+    }
+
+    /// Returns an iterator over the records (header ID and data) in the extra field
+    pub fn extra_fields(&self) -> ZipExtraFieldIterator<'_> {
+        return ZipExtraFieldIterator::new(&self.extra_field);
+    }
+
+    /// Returns ZIP64 extended information extra field (0x0001) if exists
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError::InvalidZipArchive` if the extra field is broken.
+    pub fn zip64_extended_information(
+        &self,
+    ) -> Result<Option<Zip64ExtendedInformation>, ZipReadError> {
+        return match find_extra_field(&self.extra_field, ZIP64_EXTRA_FIELD_ID) {
+            Some(data) => Ok(Some(Zip64ExtendedInformation::from_bytes(
+                data,
+                self.uncompressed_size == u32::MAX,
+                self.compressed_size == u32::MAX,
+                false,
+                false,
+            )?)),
+            None => Ok(None),
+        };
+    }
+
+    /// Returns the compressed size, taking the data descriptor and ZIP64 extended information extra field into account
+    pub fn effective_compressed_size(&self) -> u64 {
+        if let Some(data_descriptor) = &self.data_descriptor {
+            return data_descriptor.compressed_size;
+        }
+        return self
+            .zip64_extended_information()
+            .ok()
+            .flatten()
+            .and_then(|zip64| zip64.compressed_size)
+            .unwrap_or(self.compressed_size as u64);
+    }
+
+    /// Returns the uncompressed size, taking the data descriptor and ZIP64 extended information extra field into account
+    pub fn effective_uncompressed_size(&self) -> u64 {
+        if let Some(data_descriptor) = &self.data_descriptor {
+            return data_descriptor.uncompressed_size;
+        }
+        return self
+            .zip64_extended_information()
+            .ok()
+            .flatten()
+            .and_then(|zip64| zip64.uncompressed_size)
+            .unwrap_or(self.uncompressed_size as u64);
+    }
+
+    /// Sets the compressed and uncompressed sizes.
+    ///
+    /// If either of them does not fit in 32 bits, both are stored in ZIP64 extended information extra field
+    /// and `extra_field_length` is updated accordingly.
+    ///
+    /// # Arguments
+    ///
+    /// * `compressed_size` - compressed size
+    /// * `uncompressed_size` - uncompressed size
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of `ErrorKind::InvalidInput` and changes nothing if the extra field gets longer than 65535 bytes.
+    pub fn set_sizes(&mut self, compressed_size: u64, uncompressed_size: u64) -> core2::io::Result<()> {
+        if compressed_size >= u32::MAX as u64 || uncompressed_size >= u32::MAX as u64 {
+            // 4.5.3: the local header MUST include BOTH original and compressed file size fields.
+            let zip64 = Zip64ExtendedInformation {
+                uncompressed_size: Some(uncompressed_size),
+                compressed_size: Some(compressed_size),
+                local_header_position: None,
+                disk_number_start: None,
+            };
+            self.extra_field_length = replace_extra_field(
+                &mut self.extra_field,
+                ZIP64_EXTRA_FIELD_ID,
+                Some(&zip64.to_bytes()),
+            )?;
+            self.compressed_size = u32::MAX;
+            self.uncompressed_size = u32::MAX;
+            if self.version_required_to_extract < ZIP64_VERSION_REQUIRED_TO_EXTRACT {
+                self.version_required_to_extract = ZIP64_VERSION_REQUIRED_TO_EXTRACT;
+            }
+        } else {
+            self.extra_field_length =
+                replace_extra_field(&mut self.extra_field, ZIP64_EXTRA_FIELD_ID, None)?;
+            self.compressed_size = compressed_size as u32;
+            self.uncompressed_size = uncompressed_size as u32;
+        }
+        return Ok(());
+    }
+
     /// Replaces the file comment
     ///
     /// # Arguments
@@ -244,20 +863,295 @@ impl ZipLocalFileHeader<'_> {
         return Ok(ret);
     }
 
+    /// Reads local file header from the current position without seeking.
+    ///
+    /// If the sizes are unknown because of a data descriptor, the end of the file content is located by decoding it;
+    /// encrypted content and content of unsupported compression methods end at the first data descriptor whose compressed size matches.
+    /// `read` is left at the end of the entry (after the data descriptor if exists);
+    /// bytes after it stay in the buffer of `read`.
+    ///
+    /// # Arguments
+    ///
+    /// * `read` - `BufRead` object (must be at the start of the signature)
+    /// * `starting_position_with_signature` - **absolute** position of the signature, recorded in the result
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError::InvalidZipArchive` if the sizes are unknown and no matching data descriptor is found.
+    pub fn read_forward_only<T: BufRead>(
+        read: &mut T,
+        starting_position_with_signature: u64,
+    ) -> Result<Self, ZipReadError> {
+        let mut signature_candidate: [u8; 4] = [0; 4];
+        read.read_exact(&mut signature_candidate)?;
+        if signature_candidate != LOCAL_FILE_MAGIC {
+            return Err(ZipReadError::InvalidZipArchive {
+                reason: format!(
+                    "assumed local file header signature doesn't appear at position {}",
+                    starting_position_with_signature
+                ),
+            });
+        }
+        return Self::read_forward_only_without_signature(read, starting_position_with_signature);
+    }
+
+    /// Same as `read_forward_only` except that the signature has already been read
+    ///
+    /// # Arguments
+    ///
+    /// * `read` - `BufRead` object (must be at the next to the signature)
+    /// * `starting_position_with_signature` - **absolute** position of the signature, recorded in the result
+    pub(crate) fn read_forward_only_without_signature<T: BufRead>(
+        read: &mut T,
+        starting_position_with_signature: u64,
+    ) -> Result<Self, ZipReadError> {
+        let mut ret = Self::empty();
+        ret.read_header_without_signature(
+            read,
+            starting_position_with_signature + LOCAL_FILE_MAGIC.len() as u64,
+        )?;
+        ret.read_data_or_decode(read)?;
+        return Ok(ret);
+    }
+
     /// Examines the signature, reads the local file header and returns an instance that represents it
     ///
+    /// If bit #3 of general purpose flag is set, the size of the file content is taken from the central directory
+    /// and the data descriptor is checked against it.
+    ///
     /// # Arguments
     ///
-    /// * `read` - file handler (must be at the head of the signature)
+    /// * `read` - file handler
+    /// * `cd` - central directory entry of the file
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError::InvalidZipArchive` if the size of the file content exceeds the end of `read`.
     pub fn from_central_directory<T: ReadBytesExt + core2::io::Seek>(
         read: &mut T,
         cd: &ZipCDEntry,
     ) -> Result<Self, ZipReadError> {
-        read.seek(SeekFrom::Start(cd.local_header_position as u64))?;
-        let ret = Self::read_and_generate_from_signature(read)?;
+        read.seek(SeekFrom::Start(cd.effective_local_header_position()))?;
+        let mut ret = Self::read_header_from_signature(read)?;
+        let compressed_size = if ret.has_data_descriptor_by_flag() {
+            cd.effective_compressed_size()
+        } else {
+            ret.effective_compressed_size()
+        };
+        let data_position = read.seek(SeekFrom::Current(0))?;
+        let archive_size = read.seek(SeekFrom::End(0))?;
+        if compressed_size > archive_size.saturating_sub(data_position) {
+            return Err(ZipReadError::InvalidZipArchive {
+                reason: format!(
+                    "compressed size ({}) exceeds the end of the archive (data starts at {} of {} bytes)",
+                    compressed_size, data_position, archive_size
+                ),
+            });
+        }
+        read.seek(SeekFrom::Start(data_position))?;
+        ret.read_data(read, compressed_size)?;
+        if let Some(data_descriptor) = &ret.data_descriptor {
+            data_descriptor.check_against_central_directory(cd)?;
+        }
+        return Ok(ret);
+    }
+
+    /// Reads only the local file header (without the file content) from the current position.
+    ///
+    /// `compressed_data` is left empty and `data_descriptor` is `None`; `read` is left at the start of the file content.
+    /// Use `compressed_data_reader_from` or `decompressed_data_reader_from` to stream the content.
+    ///
+    /// # Arguments
+    ///
+    /// * `read` - file handler (must be at the start of the signature)
+    pub fn read_header_from_signature<T: ReadBytesExt + core2::io::Seek>(
+        read: &mut T,
+    ) -> Result<Self, ZipReadError> {
+        let mut signature_candidate: [u8; 4] = [0; 4];
+        let start_pos = read.seek(SeekFrom::Current(0))?;
+        read.read_exact(&mut signature_candidate)?;
+        if signature_candidate != LOCAL_FILE_MAGIC {
+            return Err(ZipReadError::InvalidZipArchive {
+                reason: format!(
+                    "assumed local file header signature doesn't appear at position {}",
+                    start_pos
+                ),
+            });
+        }
+        let mut ret = Self::empty();
+        ret.read_header_without_signature(read, start_pos + LOCAL_FILE_MAGIC.len() as u64)?;
+        return Ok(ret);
+    }
+
+    /// Reads only the local file header (without the file content) for the given central directory entry.
+    ///
+    /// See `read_header_from_signature`. If bit #3 of general purpose flag is set, the data descriptor is also read
+    /// (using the size in the central directory) and checked against the central directory,
+    /// so that `effective_compressed_size` and `data_range` are valid.
+    ///
+    /// # Arguments
+    ///
+    /// * `read` - file handler
+    /// * `cd` - central directory entry of the file
+    pub fn header_from_central_directory<T: ReadBytesExt + core2::io::Seek>(
+        read: &mut T,
+        cd: &ZipCDEntry,
+    ) -> Result<Self, ZipReadError> {
+        read.seek(SeekFrom::Start(cd.effective_local_header_position()))?;
+        let mut ret = Self::read_header_from_signature(read)?;
+        if ret.has_data_descriptor_by_flag() {
+            let data_starting_position = ret.data_starting_position();
+            read.seek(SeekFrom::Start(
+                data_starting_position + cd.effective_compressed_size(),
+            ))?;
+            let is_zip64 = ret.zip64_extended_information()?.is_some();
+            let data_descriptor = ZipDataDescriptor::from_reader(read, is_zip64)?;
+            data_descriptor.check_against_central_directory(cd)?;
+            ret.data_descriptor = Some(data_descriptor);
+            read.seek(SeekFrom::Start(data_starting_position))?;
+        }
         return Ok(ret);
     }
 
+    /// Returns the **absolute** position of the start of the file content
+    pub fn data_starting_position(&self) -> u64 {
+        return self.starting_position_with_signature
+            + 30
+            + self.file_name_length as u64
+            + self.extra_field_length as u64;
+    }
+
+    /// Returns the **absolute** byte range of the file content in the archive
+    pub fn data_range(&self) -> core::ops::Range<u64> {
+        let start = self.data_starting_position();
+        return start..start + self.effective_compressed_size();
+    }
+
+    /// Seeks to the file content and returns a `Read` object bounded to it
+    ///
+    /// The content is not loaded into memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `read` - file handler of the archive
+    pub fn compressed_data_reader_from<'r, T: ReadBytesExt + core2::io::Seek>(
+        &self,
+        read: &'r mut T,
+    ) -> Result<Take<&'r mut T>, ZipReadError> {
+        read.seek(SeekFrom::Start(self.data_starting_position()))?;
+        return Ok(read.take(self.effective_compressed_size()));
+    }
+
+    /// Seeks to the file content and returns a `Read` object that decompresses it on the fly and verifies its CRC-32 at the end
+    ///
+    /// # Arguments
+    ///
+    /// * `read` - file handler of the archive
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError::UnsupportedZipArchive` if the compression method is not supported or the file content is encrypted.
+    pub fn decompressed_data_reader_from<'r, T: ReadBytesExt + core2::io::Seek>(
+        &self,
+        read: &'r mut T,
+    ) -> Result<ZipDecompressReader<Take<&'r mut T>>, ZipReadError> {
+        check_not_encrypted(self.general_purpose_flags)?;
+        let compressed_data = self.compressed_data_reader_from(read)?;
+        return Ok(
+            ZipDecompressReader::new(compressed_data, self.compression_method)?
+                .with_entry_info(self.general_purpose_flags, self.effective_uncompressed_size())
+                .with_expected_crc32(self.expected_crc32()),
+        );
+    }
+
+    /// Returns CRC-32 of the file content recorded in the data descriptor (if exists) or this header
+    pub fn expected_crc32(&self) -> u32 {
+        return match &self.data_descriptor {
+            Some(data_descriptor) => data_descriptor.crc32,
+            None => self.crc32,
+        };
+    }
+
+    /// Returns a `Read` object that decompresses `compressed_data` and verifies its CRC-32 at the end
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError::UnsupportedZipArchive` if the compression method is not supported or the file content is encrypted.
+    pub fn decompressed_data_reader(&self) -> Result<ZipDecompressReader<&[u8]>, ZipReadError> {
+        check_not_encrypted(self.general_purpose_flags)?;
+        return Ok(
+            ZipDecompressReader::new(self.compressed_data.as_ref(), self.compression_method)?
+                .with_entry_info(self.general_purpose_flags, self.effective_uncompressed_size())
+                .with_expected_crc32(self.expected_crc32()),
+        );
+    }
+
+    /// Decompresses `compressed_data` and returns the file content
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError::UnsupportedZipArchive` if the compression method is not supported or the file content is encrypted,
+    /// `ZipReadError::InvalidZipArchive` if the compressed data is broken,
+    /// or `ZipReadError::Crc32Mismatch` if CRC-32 of the content differs from `expected_crc32()`.
+    pub fn decompress(&self) -> Result<Vec<u8>, ZipReadError> {
+        return self
+            .decompressed_data_reader()?
+            .read_all_decompressed(self.effective_uncompressed_size() as usize);
+    }
+
+    /// Returns whether the file content is encrypted
+    pub fn is_encrypted_data(&self) -> bool {
+        return (DATA_ENCRYPTED_FLAG_BIT & self.general_purpose_flags) != 0;
+    }
+
+    /// Returns a `Read` object that decrypts and decompresses `compressed_data` and verifies its CRC-32 at the end
+    ///
+    /// The traditional PKWARE encryption and WinZip AES (AE-1/AE-2) are supported.
+    /// For the traditional PKWARE encryption, the password is verified with the encryption header,
+    /// which may accept a wrong password with a probability of 1/256; then the CRC-32 check fails.
+    /// For WinZip AES, the password verification value is checked first,
+    /// and the authentication code is checked at the end of the data
+    /// (`ZipReadError::AuthenticationCodeMismatch` from `ZipDecompressReader::read_decompressed` if it mismatches).
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - byte sequence of the password (ignored if the file content is not encrypted)
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError::InvalidPassword` if the password is incorrect,
+    /// or `ZipReadError::UnsupportedZipArchive` if the compression method is not supported.
+    pub fn decompressed_data_reader_with_password(
+        &self,
+        password: &[u8],
+    ) -> Result<ZipDecompressReader<ZipDecryptReader<&[u8]>>, ZipReadError> {
+        return decrypted_data_reader(
+            self.compressed_data.as_ref(),
+            self.general_purpose_flags,
+            self.compression_method,
+            &self.extra_field,
+            password_check_byte(self.general_purpose_flags, self.crc32, self.last_mod_time),
+            self.expected_crc32(),
+            self.effective_uncompressed_size(),
+            password,
+        );
+    }
+
+    /// Decrypts and decompresses `compressed_data` and returns the file content
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - byte sequence of the password (ignored if the file content is not encrypted)
+    ///
+    /// # Errors
+    ///
+    /// See `decompressed_data_reader_with_password` and `decompress`.
+    pub fn decompress_with_password(&self, password: &[u8]) -> Result<Vec<u8>, ZipReadError> {
+        return self
+            .decompressed_data_reader_with_password(password)?
+            .read_all_decompressed(self.effective_uncompressed_size() as usize);
+    }
+
     /// Writes the content of this local file header to file and returns the number of bytes written.
     ///
     /// # Arguments
@@ -267,7 +1161,7 @@ impl ZipLocalFileHeader<'_> {
         let mut bytes_written = 30
             + self.file_name_length as u64
             + self.extra_field_length as u64
-            + self.compressed_size as u64;
+            + self.compressed_data.len() as u64;
         write.write_all(&LOCAL_FILE_MAGIC)?;
         write.write_u16::<LE>(self.version_required_to_extract)?;
         write.write_u16::<LE>(self.general_purpose_flags)?;
@@ -288,3 +1182,305 @@ impl ZipLocalFileHeader<'_> {
         return Ok(bytes_written);
     }
 }
+
+/// バイト列からファイル名・拡張フィールド・ファイル内容を借用するローカルファイルヘッダ /
+/// Local file header borrowing the file name, extra field and file content from a byte sequence
+///
+/// Generated by `from_bytes` without copying; see `ZipLocalFileHeader` for the meaning of the fields.
+pub struct ZipLocalFileHeaderRef<'a> {
+    pub version_required_to_extract: u16,
+    pub general_purpose_flags: u16,
+    pub compression_method: u16,
+    pub last_mod_time: u16,
+    pub last_mod_date: u16,
+    pub crc32: u32,
+    pub compressed_size: u32,
+    pub uncompressed_size: u32,
+    pub file_name_length: u16,
+    pub extra_field_length: u16,
+    /// Byte sequence of the file name (borrowed)
+    pub file_name_raw: &'a [u8],
+    /// Byte sequence of extra field (borrowed)
+    pub extra_field: &'a [u8],
+    /// File content (borrowed)
+    pub compressed_data: &'a [u8],
+    pub data_descriptor: Option<ZipDataDescriptor>,
+    pub starting_position_with_signature: u64,
+    pub starting_position_without_signature: u64,
+}
+
+impl<'a> ZipLocalFileHeaderRef<'a> {
+    /// Reads a local file header and its file content at the start of `bytes`
+    ///
+    /// The starting positions are relative to `bytes`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - byte sequence starting with the signature
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, ZipReadError> {
+        return Self::from_bytes_at(bytes, 0);
+    }
+
+    /// Reads a local file header and its file content at `position` of a whole ZIP archive on memory
+    ///
+    /// # Arguments
+    ///
+    /// * `archive` - byte sequence of the whole ZIP archive
+    /// * `position` - **absolute** position of the signature
+    pub fn from_bytes_at(archive: &'a [u8], position: u64) -> Result<Self, ZipReadError> {
+        let (mut result, mut read) = Self::header_from_bytes_at(archive, position)?;
+        let compressed_size = result.header_compressed_size()?;
+        if (DATA_DESCRIPTOR_EXISTS_FLAG_BIT & result.general_purpose_flags) != 0 && compressed_size == 0 {
+            let is_zip64 = result.zip64_extended_information()?.is_some();
+            let data = read;
+            let (compressed_data, data_descriptor) = read_data_and_data_descriptor(
+                &mut read,
+                result.compression_method,
+                result.general_purpose_flags,
+                is_zip64,
+            )?;
+            result.compressed_data = &data[..compressed_data.len()];
+            result.data_descriptor = Some(data_descriptor);
+            return Ok(result);
+        }
+        result.read_data(&mut read, compressed_size)?;
+        return Ok(result);
+    }
+
+    /// Reads the local file header at `position` and returns it with the rest of `archive` after the header
+    fn header_from_bytes_at(
+        archive: &'a [u8],
+        position: u64,
+    ) -> Result<(Self, &'a [u8]), ZipReadError> {
+        let mut read = slice_from(archive, position).unwrap_or(&[]);
+        if take_slice(&mut read, LOCAL_FILE_MAGIC.len() as u64) != LOCAL_FILE_MAGIC {
+            return Err(ZipReadError::InvalidZipArchive {
+                reason: format!(
+                    "assumed local file header signature doesn't appear at position {}",
+                    position
+                ),
+            });
+        }
+        let mut result = Self {
+            version_required_to_extract: read.read_u16::<LE>()?,
+            general_purpose_flags: read.read_u16::<LE>()?,
+            compression_method: read.read_u16::<LE>()?,
+            last_mod_time: read.read_u16::<LE>()?,
+            last_mod_date: read.read_u16::<LE>()?,
+            crc32: read.read_u32::<LE>()?,
+            compressed_size: read.read_u32::<LE>()?,
+            uncompressed_size: read.read_u32::<LE>()?,
+            file_name_length: read.read_u16::<LE>()?,
+            extra_field_length: read.read_u16::<LE>()?,
+            file_name_raw: &[],
+            extra_field: &[],
+            compressed_data: &[],
+            data_descriptor: None,
+            starting_position_with_signature: position,
+            starting_position_without_signature: position + LOCAL_FILE_MAGIC.len() as u64,
+        };
+        result.file_name_raw = take_slice(&mut read, result.file_name_length as u64);
+        if result.file_name_raw.len() != result.file_name_length as usize {
+            return Err(ZipReadError::InvalidZipArchive {
+                reason: format!(
+                    "file name length is invalid (expected from length value field: {} / got: {})",
+                    result.file_name_length,
+                    result.file_name_raw.len()
+                ),
+            });
+        }
+        result.extra_field = take_slice(&mut read, result.extra_field_length as u64);
+        if result.extra_field.len() != result.extra_field_length as usize {
+            return Err(ZipReadError::InvalidZipArchive {
+                reason: format!(
+                    "extra field length is invalid (expected from length value field: {} / got {}",
+                    result.extra_field_length,
+                    result.extra_field.len()
+                ),
+            });
+        }
+        return Ok((result, read));
+    }
+
+    /// Borrows the file content (and reads the data descriptor if exists) from `read`
+    ///
+    /// # Arguments
+    ///
+    /// * `read` - byte sequence starting with the file content
+    /// * `compressed_size` - size of the file content
+    fn read_data(&mut self, read: &mut &'a [u8], compressed_size: u64) -> Result<(), ZipReadError> {
+        self.compressed_data = take_slice(read, compressed_size);
+        if self.compressed_data.len() as u64 != compressed_size {
+            return Err(ZipReadError::InvalidZipArchive {
+                reason: format!(
+                    "compressed size is invalid (expected from length value field: {} / got {}",
+                    compressed_size,
+                    self.compressed_data.len()
+                ),
+            });
+        }
+        if (DATA_DESCRIPTOR_EXISTS_FLAG_BIT & self.general_purpose_flags) != 0 {
+            let is_zip64 = self.zip64_extended_information()?.is_some();
+            self.data_descriptor = Some(ZipDataDescriptor::from_reader(read, is_zip64)?);
+        }
+        return Ok(());
+    }
+
+    /// Returns the compressed size recorded in this header, taking ZIP64 extended information extra field into account
+    fn header_compressed_size(&self) -> Result<u64, ZipReadError> {
+        return Ok(match self.zip64_extended_information()? {
+            Some(zip64) => zip64.compressed_size.unwrap_or(self.compressed_size as u64),
+            None => self.compressed_size as u64,
+        });
+    }
+
+    /// Reads the local file header and its file content for the given central directory entry
+    ///
+    /// If bit #3 of general purpose flag is set, the size of the file content is taken from the central directory
+    /// and the data descriptor is checked against it.
+    ///
+    /// # Arguments
+    ///
+    /// * `archive` - byte sequence of the whole ZIP archive
+    /// * `cd` - central directory entry of the file
+    pub fn from_central_directory(
+        archive: &'a [u8],
+        cd: &ZipCDEntryRef,
+    ) -> Result<Self, ZipReadError> {
+        let (mut result, mut read) =
+            Self::header_from_bytes_at(archive, cd.effective_local_header_position())?;
+        let compressed_size = if (DATA_DESCRIPTOR_EXISTS_FLAG_BIT & result.general_purpose_flags) != 0 {
+            cd.effective_compressed_size()
+        } else {
+            result.header_compressed_size()?
+        };
+        result.read_data(&mut read, compressed_size)?;
+        if let Some(data_descriptor) = &result.data_descriptor {
+            check_data_descriptor(
+                data_descriptor,
+                cd.crc32,
+                cd.effective_compressed_size(),
+                cd.effective_uncompressed_size(),
+            )?;
+        }
+        return Ok(result);
+    }
+
+    /// Returns an iterator over the records (header ID and data) in the extra field
+    pub fn extra_fields(&self) -> ZipExtraFieldIterator<'a> {
+        return ZipExtraFieldIterator::new(self.extra_field);
+    }
+
+    /// Returns ZIP64 extended information extra field (0x0001) if exists
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError::InvalidZipArchive` if the extra field is broken.
+    pub fn zip64_extended_information(
+        &self,
+    ) -> Result<Option<Zip64ExtendedInformation>, ZipReadError> {
+        return match find_extra_field(self.extra_field, ZIP64_EXTRA_FIELD_ID) {
+            Some(data) => Ok(Some(Zip64ExtendedInformation::from_bytes(
+                data,
+                self.uncompressed_size == u32::MAX,
+                self.compressed_size == u32::MAX,
+                false,
+                false,
+            )?)),
+            None => Ok(None),
+        };
+    }
+
+    /// Returns the uncompressed size, taking the data descriptor and ZIP64 extended information extra field into account
+    pub fn effective_uncompressed_size(&self) -> u64 {
+        if let Some(data_descriptor) = &self.data_descriptor {
+            return data_descriptor.uncompressed_size;
+        }
+        return self
+            .zip64_extended_information()
+            .ok()
+            .flatten()
+            .and_then(|zip64| zip64.uncompressed_size)
+            .unwrap_or(self.uncompressed_size as u64);
+    }
+
+    /// Returns CRC-32 of the file content recorded in the data descriptor (if exists) or this header
+    pub fn expected_crc32(&self) -> u32 {
+        return match &self.data_descriptor {
+            Some(data_descriptor) => data_descriptor.crc32,
+            None => self.crc32,
+        };
+    }
+
+    /// Returns a `Read` object that decompresses `compressed_data` and verifies its CRC-32 at the end
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError::UnsupportedZipArchive` if the compression method is not supported or the file content is encrypted.
+    pub fn decompressed_data_reader(&self) -> Result<ZipDecompressReader<&'a [u8]>, ZipReadError> {
+        check_not_encrypted(self.general_purpose_flags)?;
+        return Ok(
+            ZipDecompressReader::new(self.compressed_data, self.compression_method)?
+                .with_entry_info(self.general_purpose_flags, self.effective_uncompressed_size())
+                .with_expected_crc32(self.expected_crc32()),
+        );
+    }
+
+    /// Decompresses `compressed_data` and returns the file content
+    ///
+    /// # Errors
+    ///
+    /// See `ZipLocalFileHeader::decompress`.
+    pub fn decompress(&self) -> Result<Vec<u8>, ZipReadError> {
+        return self
+            .decompressed_data_reader()?
+            .read_all_decompressed(self.effective_uncompressed_size() as usize);
+    }
+
+    /// Decrypts and decompresses `compressed_data` and returns the file content
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - byte sequence of the password (ignored if the file content is not encrypted)
+    ///
+    /// # Errors
+    ///
+    /// See `ZipLocalFileHeader::decompress_with_password`.
+    pub fn decompress_with_password(&self, password: &[u8]) -> Result<Vec<u8>, ZipReadError> {
+        return decrypted_data_reader(
+            self.compressed_data,
+            self.general_purpose_flags,
+            self.compression_method,
+            self.extra_field,
+            password_check_byte(self.general_purpose_flags, self.crc32, self.last_mod_time),
+            self.expected_crc32(),
+            self.effective_uncompressed_size(),
+            password,
+        )?
+        .read_all_decompressed(self.effective_uncompressed_size() as usize);
+    }
+
+    /// Converts into `ZipLocalFileHeader` by copying the file name and extra field
+    ///
+    /// The file content stays borrowed.
+    pub fn into_owned(self) -> ZipLocalFileHeader<'a> {
+        return ZipLocalFileHeader {
+            version_required_to_extract: self.version_required_to_extract,
+            general_purpose_flags: self.general_purpose_flags,
+            compression_method: self.compression_method,
+            last_mod_time: self.last_mod_time,
+            last_mod_date: self.last_mod_date,
+            crc32: self.crc32,
+            compressed_size: self.compressed_size,
+            uncompressed_size: self.uncompressed_size,
+            file_name_length: self.file_name_length,
+            extra_field_length: self.extra_field_length,
+            file_name_raw: self.file_name_raw.to_vec(),
+            extra_field: self.extra_field.to_vec(),
+            compressed_data: Cow::Borrowed(self.compressed_data),
+            data_descriptor: self.data_descriptor,
+            starting_position_with_signature: self.starting_position_with_signature,
+            starting_position_without_signature: self.starting_position_without_signature,
+        };
+    }
+}
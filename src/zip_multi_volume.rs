@@ -0,0 +1,226 @@
+use super::zip_central_directory::ZipCDEntry;
+use super::zip_eocd::ZipEOCD;
+use super::zip_error::ZipReadError;
+use byteorder::ReadBytesExt;
+
+use alloc::format;
+use alloc::vec;
+use alloc::vec::Vec;
+use core2::io::{Error, ErrorKind, Read, Seek, SeekFrom};
+
+/// 分割・スパンされたZIPアーカイブの各ボリュームを連結して読むリーダー /
+/// Reader that reads the volumes (segments) of a split or spanned ZIP archive as one continuous stream
+///
+/// Volumes are given in the order of the disk numbers (e.g. `.z01`, `.z02`, ..., `.zip`).
+/// A location in the ZIP structures (disk number and offset in the disk) is converted by `absolute_position`,
+/// and `read_central_directory` converts the EOCD and central directory into positions in the joined stream,
+/// so that the central directory, local file headers and file contents can be read across volumes.
+pub struct ZipMultiVolumeReader<R: ReadBytesExt + Seek> {
+    /// file handlers of the volumes in the order of the disk numbers
+    volumes: Vec<R>,
+    /// **absolute** starting positions of the volumes, followed by the total size
+    volume_starting_positions: Vec<u64>,
+    /// **absolute** position of the next byte to be read
+    position: u64,
+    /// index of the volume whose read position corresponds to `position` (if any)
+    current_volume: Option<usize>,
+}
+
+impl<R: ReadBytesExt + Seek> ZipMultiVolumeReader<R> {
+    /// Joins the volumes of an archive
+    ///
+    /// # Arguments
+    ///
+    /// * `volumes` - file handlers of the volumes in the order of the disk numbers (the one with EOCD is the last)
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError::InvalidZipArchive` if no volumes are given,
+    /// or `ZipReadError::IOError` if the size of a volume cannot be determined.
+    pub fn new(mut volumes: Vec<R>) -> Result<Self, ZipReadError> {
+        if volumes.is_empty() {
+            return Err(ZipReadError::InvalidZipArchive {
+                reason: "no volumes are given".into(),
+            });
+        }
+        let mut volume_starting_positions: Vec<u64> = vec![0];
+        let mut total_size: u64 = 0;
+        for volume in &mut volumes {
+            total_size += volume.seek(SeekFrom::End(0))?;
+            volume_starting_positions.push(total_size);
+        }
+        return Ok(Self {
+            volumes,
+            volume_starting_positions,
+            position: 0,
+            current_volume: None,
+        });
+    }
+
+    /// Returns the number of volumes
+    pub fn n_volumes(&self) -> usize {
+        return self.volumes.len();
+    }
+
+    /// Returns the total size of the volumes
+    pub fn total_size(&self) -> u64 {
+        return self.volume_starting_positions[self.volumes.len()];
+    }
+
+    /// Converts a disk number and an offset in the disk into the **absolute** position in the joined stream
+    ///
+    /// # Arguments
+    ///
+    /// * `disk_index` - number of the disk (0-based; e.g. `disk_number_start` of a central directory entry)
+    /// * `offset` - offset from the start of the disk
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError::InvalidZipArchive` if the disk does not exist or the offset is beyond its end.
+    pub fn absolute_position(&self, disk_index: u32, offset: u64) -> Result<u64, ZipReadError> {
+        return absolute_position(&self.volume_starting_positions, disk_index, offset);
+    }
+
+    /// Reads the EOCD and central directory, and converts their disk numbers and offsets into **absolute** positions
+    ///
+    /// The returned structures look like the ones of a single archive:
+    /// the disk numbers are 0 and the positions point into this reader.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError` if the archive is broken or not supported.
+    pub fn read_central_directory(&mut self) -> Result<(ZipEOCD, Vec<ZipCDEntry>), ZipReadError> {
+        let volume_starting_positions = self.volume_starting_positions.clone();
+        let mut eocd = ZipEOCD::from_reader_with_disk_positions(self, &|disk_index, offset| {
+            absolute_position(&volume_starting_positions, disk_index, offset)
+        })?;
+        let cd_starting_position = self.absolute_position(
+            eocd.effective_cd_start_disk_index(),
+            eocd.effective_cd_starting_position(),
+        )?;
+        eocd.eocd_disk_index = 0;
+        eocd.cd_start_disk_index = 0;
+        eocd.n_cd_entries_in_disk = eocd.n_cd_entries;
+        match &mut eocd.zip64 {
+            Some(zip64) => {
+                zip64.eocd_disk_index = 0;
+                zip64.cd_start_disk_index = 0;
+                zip64.n_cd_entries_in_disk = zip64.n_cd_entries;
+                zip64.cd_starting_position = cd_starting_position;
+            }
+            None => {
+                if cd_starting_position >= u32::MAX as u64 {
+                    return Err(ZipReadError::UnsupportedZipArchive {
+                        reason: "the central directory starts beyond 4 GiB of the volumes without ZIP64 EOCD record".into(),
+                    });
+                }
+                eocd.cd_starting_position = cd_starting_position as u32;
+            }
+        }
+        eocd.check_unsupported_zip_type()?;
+        let mut cd_list = ZipCDEntry::all_from_eocd_unchecked(self, &eocd)?;
+        for cd in &mut cd_list {
+            let local_header_position = self.absolute_position(
+                cd.effective_disk_number_start(),
+                cd.effective_local_header_position(),
+            )?;
+            cd.set_disk_number_start(0)?;
+            cd.set_local_header_position(local_header_position)?;
+            cd.check_unsupported()?;
+        }
+        return Ok((eocd, cd_list));
+    }
+
+    /// Returns the file handlers of the volumes
+    pub fn into_inner(self) -> Vec<R> {
+        return self.volumes;
+    }
+}
+
+/// Converts a disk number and an offset in the disk into the **absolute** position
+///
+/// # Arguments
+///
+/// * `volume_starting_positions` - see `ZipMultiVolumeReader::volume_starting_positions`
+/// * `disk_index` - number of the disk (0-based)
+/// * `offset` - offset from the start of the disk
+fn absolute_position(
+    volume_starting_positions: &[u64],
+    disk_index: u32,
+    offset: u64,
+) -> Result<u64, ZipReadError> {
+    let disk_index = disk_index as usize;
+    if disk_index + 1 >= volume_starting_positions.len() {
+        return Err(ZipReadError::InvalidZipArchive {
+            reason: format!(
+                "disk {} does not exist (number of volumes: {})",
+                disk_index,
+                volume_starting_positions.len() - 1
+            ),
+        });
+    }
+    let volume_starting_position = volume_starting_positions[disk_index];
+    let volume_size = volume_starting_positions[disk_index + 1] - volume_starting_position;
+    if offset > volume_size {
+        return Err(ZipReadError::InvalidZipArchive {
+            reason: format!(
+                "offset {} is beyond the end of disk {} (size: {})",
+                offset, disk_index, volume_size
+            ),
+        });
+    }
+    return Ok(volume_starting_position + offset);
+}
+
+impl<R: ReadBytesExt + Seek> Read for ZipMultiVolumeReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> core2::io::Result<usize> {
+        if buf.is_empty() || self.position >= self.total_size() {
+            return Ok(0);
+        }
+        // the last volume starting at or before the position (empty volumes are skipped)
+        let index = self
+            .volume_starting_positions
+            .partition_point(|start| *start <= self.position)
+            - 1;
+        let volume_starting_position = self.volume_starting_positions[index];
+        if self.current_volume != Some(index) {
+            self.volumes[index].seek(SeekFrom::Start(self.position - volume_starting_position))?;
+            self.current_volume = Some(index);
+        }
+        let remaining = self.volume_starting_positions[index + 1] - self.position;
+        let max_length = core::cmp::min(buf.len() as u64, remaining) as usize;
+        let n_read = self.volumes[index].read(&mut buf[..max_length])?;
+        if n_read == 0 {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                "a volume is shorter than its size at the start",
+            ));
+        }
+        self.position += n_read as u64;
+        return Ok(n_read);
+    }
+}
+
+impl<R: ReadBytesExt + Seek> Seek for ZipMultiVolumeReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> core2::io::Result<u64> {
+        let (base, offset) = match pos {
+            SeekFrom::Start(position) => (position, 0),
+            SeekFrom::End(offset) => (self.total_size(), offset),
+            SeekFrom::Current(offset) => (self.position, offset),
+        };
+        let new_position = match (base as i64).checked_add(offset) {
+            Some(position) if position >= 0 => position as u64,
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "invalid seek to a negative or overflowing position",
+                ))
+            }
+        };
+        if new_position != self.position {
+            self.position = new_position;
+            self.current_volume = None;
+        }
+        return Ok(self.position);
+    }
+}
@@ -0,0 +1,130 @@
+use super::zip_central_directory::ZipCDEntry;
+
+use alloc::vec::Vec;
+
+/// Index of entries by file name built from the central directory
+///
+/// File names are compared as byte sequences.  Case-insensitive lookup folds only ASCII letters
+/// because file names are not always encoded in UTF-8.
+pub struct ZipNameIndex {
+    /// (file name, index of the entry) sorted by file name and then index
+    by_name: Vec<(Vec<u8>, usize)>,
+    /// (ASCII-lowercased file name, index of the entry) sorted by file name and then index
+    by_lowercase_name: Vec<(Vec<u8>, usize)>,
+}
+
+impl ZipNameIndex {
+    /// Builds an index from central directory entries
+    ///
+    /// # Arguments
+    ///
+    /// * `cd_list` - central directory entries (indices of this slice are returned by lookups)
+    pub fn new(cd_list: &[ZipCDEntry]) -> Self {
+        let mut by_name: Vec<(Vec<u8>, usize)> = cd_list
+            .iter()
+            .enumerate()
+            .map(|(index, cd)| (cd.file_name_raw.clone(), index))
+            .collect();
+        by_name.sort_unstable();
+        let mut by_lowercase_name: Vec<(Vec<u8>, usize)> = cd_list
+            .iter()
+            .enumerate()
+            .map(|(index, cd)| (cd.file_name_raw.to_ascii_lowercase(), index))
+            .collect();
+        by_lowercase_name.sort_unstable();
+        return Self {
+            by_name,
+            by_lowercase_name,
+        };
+    }
+
+    /// Returns the number of indexed entries
+    pub fn len(&self) -> usize {
+        return self.by_name.len();
+    }
+
+    /// Returns `true` if no entries are indexed
+    pub fn is_empty(&self) -> bool {
+        return self.by_name.is_empty();
+    }
+
+    /// Returns the index of the entry whose file name is exactly `name`
+    ///
+    /// If there are multiple such entries, the first one in the archive is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - byte sequence of the file name
+    pub fn find(&self, name: &[u8]) -> Option<usize> {
+        return find_in(&self.by_name, name);
+    }
+
+    /// Returns the index of the entry whose file name equals `name` ignoring ASCII case
+    ///
+    /// If there are multiple such entries, the first one in the archive is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - byte sequence of the file name
+    pub fn find_case_insensitive(&self, name: &[u8]) -> Option<usize> {
+        return find_in(&self.by_lowercase_name, &name.to_ascii_lowercase());
+    }
+
+    /// Returns indices of the entries whose file names start with `prefix`, in the order of file names
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - byte sequence of the prefix (e.g. `b"OEBPS/"`)
+    pub fn find_by_prefix(&self, prefix: &[u8]) -> Vec<usize> {
+        return self
+            .prefix_range(prefix)
+            .iter()
+            .map(|(_, index)| *index)
+            .collect();
+    }
+
+    /// Returns indices of the entries directly in the directory `directory`, in the order of file names
+    ///
+    /// Subdirectories are included only if they have their own entries (names ending with `/`).
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - byte sequence of the directory path with or without the trailing `/`; empty for the root
+    pub fn list_directory(&self, directory: &[u8]) -> Vec<usize> {
+        let mut prefix: Vec<u8> = directory.to_vec();
+        if !prefix.is_empty() && prefix.last() != Some(&b'/') {
+            prefix.push(b'/');
+        }
+        return self
+            .prefix_range(&prefix)
+            .iter()
+            .filter(|(name, _)| {
+                let rest = &name[prefix.len()..];
+                // exclude the directory itself and entries in its subdirectories
+                !rest.is_empty() && !rest[..rest.len() - 1].contains(&b'/')
+            })
+            .map(|(_, index)| *index)
+            .collect();
+    }
+
+    /// Returns the part of `by_name` whose file names start with `prefix`
+    fn prefix_range(&self, prefix: &[u8]) -> &[(Vec<u8>, usize)] {
+        let start = self
+            .by_name
+            .partition_point(|(name, _)| name.as_slice() < prefix);
+        let length = self.by_name[start..]
+            .iter()
+            .take_while(|(name, _)| name.starts_with(prefix))
+            .count();
+        return &self.by_name[start..start + length];
+    }
+}
+
+/// Binary search for the first entry whose name is `name` in a sorted list
+fn find_in(sorted: &[(Vec<u8>, usize)], name: &[u8]) -> Option<usize> {
+    let pos = sorted.partition_point(|(current_name, _)| current_name.as_slice() < name);
+    return match sorted.get(pos) {
+        Some((current_name, index)) if current_name.as_slice() == name => Some(*index),
+        _ => None,
+    };
+}
@@ -0,0 +1,106 @@
+use super::zip_central_directory::CD_MAGIC;
+use super::zip_eocd::{EOCD_MAGIC, ZIP64_EOCD_MAGIC};
+use super::zip_error::ZipReadError;
+use super::zip_local_file_header::{ZipLocalFileHeader, LOCAL_FILE_MAGIC};
+use core2::io::BufRead;
+
+use alloc::format;
+
+/// 先頭から順にローカルファイルヘッダを読む、シーク不要のリーダー /
+/// Reader that walks local file headers from the start of a ZIP archive without seeking
+///
+/// Used for archives that arrive as a byte stream (e.g. upload body or pipe) where the EOCD cannot be reached first.
+/// Entries whose sizes are unknown because of a data descriptor are located by decoding them
+/// (see `ZipLocalFileHeader::read_forward_only`).
+/// Reading stops at the central directory (or EOCD if there are no entries).
+///
+/// `R` must be buffered (e.g. `&[u8]` or `BufReader`) so that the bytes after an entry located by decoding are not lost.
+pub struct ZipStreamReader<R: BufRead> {
+    /// `BufRead` object of the archive
+    read: R,
+    /// **absolute** position of `read`
+    position: u64,
+    /// `true` if the central directory has been reached or an error has occurred
+    finished: bool,
+}
+
+impl<R: BufRead> ZipStreamReader<R> {
+    /// Creates a reader at the start of the archive
+    ///
+    /// # Arguments
+    ///
+    /// * `read` - `BufRead` object (must be at the start of the archive)
+    pub fn new(read: R) -> Self {
+        return Self {
+            read,
+            position: 0,
+            finished: false,
+        };
+    }
+
+    /// Returns the **absolute** position of the next local file header
+    /// (or just after the signature of the central directory if it has been reached)
+    pub fn position(&self) -> u64 {
+        return self.position;
+    }
+
+    /// Returns `true` if the central directory has been reached or an error has occurred
+    pub fn is_finished(&self) -> bool {
+        return self.finished;
+    }
+
+    /// Reads the next local file header with its file content
+    ///
+    /// Returns `Ok(None)` when the central directory (or EOCD) is reached; its signature is consumed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZipReadError::InvalidZipArchive` if an unknown signature appears,
+    /// or the error of `ZipLocalFileHeader::read_forward_only`.
+    /// No more entries are read after an error.
+    pub fn next_entry(&mut self) -> Result<Option<ZipLocalFileHeader<'static>>, ZipReadError> {
+        if self.finished {
+            return Ok(None);
+        }
+        // Stops here if anything below fails
+        self.finished = true;
+        let mut signature: [u8; 4] = [0; 4];
+        self.read.read_exact(&mut signature)?;
+        if signature == CD_MAGIC || signature == EOCD_MAGIC || signature == ZIP64_EOCD_MAGIC {
+            self.position += signature.len() as u64;
+            return Ok(None);
+        }
+        if signature != LOCAL_FILE_MAGIC {
+            return Err(ZipReadError::InvalidZipArchive {
+                reason: format!(
+                    "neither local file header nor central directory signature appears at position {}",
+                    self.position
+                ),
+            });
+        }
+        let local_file_header =
+            ZipLocalFileHeader::read_forward_only_without_signature(&mut self.read, self.position)?;
+        let data_descriptor_size = match &local_file_header.data_descriptor {
+            Some(data_descriptor) => data_descriptor.size(),
+            None => 0,
+        };
+        self.position = local_file_header.data_starting_position()
+            + local_file_header.compressed_data.len() as u64
+            + data_descriptor_size;
+        self.finished = false;
+        return Ok(Some(local_file_header));
+    }
+
+    /// Returns the `BufRead` object
+    pub fn into_inner(self) -> R {
+        return self.read;
+    }
+}
+
+impl<R: BufRead> Iterator for ZipStreamReader<R> {
+    type Item = Result<ZipLocalFileHeader<'static>, ZipReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        return self.next_entry().transpose();
+    }
+}
@@ -0,0 +1,107 @@
+#![feature(core_intrinsics,lang_items,start,default_alloc_error_handler,custom_test_frameworks)]
+#![no_std]
+#![no_main]
+#![test_runner(test_runner)]
+#![reexport_test_harness_main = "test_main"]
+
+
+pub fn test_runner(tests: &[&dyn Fn()]) {
+
+    for test in tests {
+        test();
+    }
+
+}
+
+#[cfg(test)]
+#[start]
+#[no_mangle]
+pub extern "C" fn _start() {
+    test_main();
+}
+
+
+#[cfg(test)]
+    mod test {
+
+    use zip_structs::zip_central_directory::ZipCDEntry;
+    use zip_structs::zip_eocd::ZipEOCD;
+    use zip_structs::zip_extra_field::*;
+
+    extern crate  alloc;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use core::include_bytes;
+    use core2::io::Cursor;
+
+    #[test_case]
+    fn extra_field_build_and_parse_test() {
+        let mut zip_file = Cursor::new(include_bytes!("./assets/explicit_utf-8.zip"));
+        let eocd = ZipEOCD::from_reader(&mut zip_file).unwrap();
+        let mut cd = ZipCDEntry::all_from_eocd(&mut zip_file, &eocd).unwrap().pop().unwrap();
+        assert_eq!(cd.extra_fields().count(), 0);
+
+        let extra_field = ZipExtraFieldBuilder::from_extra_field(&cd.extra_field)
+            .extended_timestamp(&ExtendedTimestamp {
+                flags: 0x01,
+                mtime: Some(1_600_000_000),
+                atime: None,
+                ctime: None,
+            })
+            .info_zip_unix(&InfoZipUnix {
+                version: 1,
+                uid: 1000,
+                gid: 1000,
+            })
+            .build()
+            .unwrap();
+        cd.set_extra_field_from_slice(&extra_field).unwrap();
+        assert_eq!(cd.extra_field_length as usize, cd.extra_field.len());
+
+        let header_ids: Vec<u16> = cd.extra_fields().map(|(header_id, _)| header_id).collect();
+        assert_eq!(
+            header_ids,
+            [EXTENDED_TIMESTAMP_EXTRA_FIELD_ID, INFO_ZIP_UNIX_EXTRA_FIELD_ID]
+        );
+
+        let timestamp = ExtendedTimestamp::from_bytes(
+            find_extra_field(&cd.extra_field, EXTENDED_TIMESTAMP_EXTRA_FIELD_ID).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(timestamp.mtime, Some(1_600_000_000));
+        assert_eq!(timestamp.atime, None);
+
+        let unix = InfoZipUnix::from_bytes(
+            find_extra_field(&cd.extra_field, INFO_ZIP_UNIX_EXTRA_FIELD_ID).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(unix.uid, 1000);
+        assert_eq!(unix.gid, 1000);
+    }
+
+    #[test_case]
+    fn aes_extra_field_test() {
+        let field = AesExtraField {
+            vendor_version: 2,
+            vendor_id: *b"AE",
+            strength: 3,
+            compression_method: 8,
+        };
+        let parsed = AesExtraField::from_bytes(&field.to_bytes()).unwrap();
+        assert_eq!(parsed.vendor_version, 2);
+        assert_eq!(parsed.strength, 3);
+        assert_eq!(parsed.compression_method, 8);
+        assert!(AesExtraField::from_bytes(b"\x02\x00XY\x03\x08\x00").is_err());
+    }
+
+    #[test_case]
+    fn extra_field_builder_too_long_test() {
+        // 4 bytes of header ID and data size + 65531 bytes of data = 65535 bytes
+        let builder = ZipExtraFieldBuilder::new().set(0xcafe, vec![0; 65531]);
+        assert_eq!(builder.build().unwrap().len(), u16::MAX as usize);
+        assert!(builder.set(0xcafe, vec![0; 65532]).build().is_err());
+        assert!(ZipExtraFieldBuilder::new().set(0xcafe, vec![0; 70000]).build().is_err());
+    }
+
+}
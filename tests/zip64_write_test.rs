@@ -0,0 +1,239 @@
+#![feature(core_intrinsics,lang_items,start,default_alloc_error_handler,custom_test_frameworks)]
+#![no_std]
+#![no_main]
+#![test_runner(test_runner)]
+#![reexport_test_harness_main = "test_main"]
+
+
+pub fn test_runner(tests: &[&dyn Fn()]) {
+
+    for test in tests {
+        test();
+    }
+
+}
+
+#[cfg(test)]
+#[start]
+#[no_mangle]
+pub extern "C" fn _start() {
+    test_main();
+}
+
+
+#[cfg(test)]
+    mod test {
+
+    use zip_structs::zip_archive::ZipArchive;
+    use zip_structs::zip_archive_writer::{ZipArchiveWriter, ZipEntryOptions};
+    use zip_structs::zip_central_directory::ZipCDEntry;
+    use zip_structs::zip_compression::COMPRESSION_METHOD_STORED;
+    use zip_structs::zip_eocd::ZipEOCD;
+    use zip_structs::zip_local_file_header::ZipLocalFileHeader;
+
+    extern crate  alloc;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use core::include_bytes;
+    use core2::io::{Cursor, ErrorKind, Write};
+
+    const SIZE_OVER_4GIB: u64 = 5 << 30;
+
+    /// Number of bytes kept at each end of the output of `CountingSink`
+    const KEPT_SIZE: usize = 4096;
+
+    /// `Write` object that counts the written bytes and keeps only the first and last `KEPT_SIZE` bytes
+    struct CountingSink {
+        n_written: u64,
+        head: Vec<u8>,
+        tail: Vec<u8>,
+    }
+
+    impl CountingSink {
+        fn new() -> Self {
+            return Self {
+                n_written: 0,
+                head: vec![],
+                tail: vec![],
+            };
+        }
+    }
+
+    impl Write for CountingSink {
+        fn write(&mut self, buf: &[u8]) -> core2::io::Result<usize> {
+            let n_head = core::cmp::min(buf.len(), KEPT_SIZE - self.head.len());
+            self.head.extend_from_slice(&buf[..n_head]);
+            self.tail.extend_from_slice(&buf[buf.len().saturating_sub(KEPT_SIZE)..]);
+            let n_dropped = self.tail.len().saturating_sub(KEPT_SIZE);
+            self.tail.drain(..n_dropped);
+            self.n_written += buf.len() as u64;
+            return Ok(buf.len());
+        }
+
+        fn flush(&mut self) -> core2::io::Result<()> {
+            return Ok(());
+        }
+    }
+
+    #[test_case]
+    fn zip64_cd_write_test() {
+        let mut zip_file = Cursor::new(include_bytes!("./assets/explicit_utf-8.zip"));
+        let eocd = ZipEOCD::from_reader(&mut zip_file).unwrap();
+        let mut cd = ZipCDEntry::all_from_eocd(&mut zip_file, &eocd).unwrap().pop().unwrap();
+        cd.set_sizes(SIZE_OVER_4GIB, SIZE_OVER_4GIB + 1).unwrap();
+        cd.set_local_header_position(SIZE_OVER_4GIB + 2).unwrap();
+
+        assert_eq!(cd.compressed_size, u32::MAX);
+        assert_eq!(cd.uncompressed_size, u32::MAX);
+        assert_eq!(cd.local_header_position, u32::MAX);
+        assert_eq!(cd.extra_field_length as usize, cd.extra_field.len());
+
+        let mut out: Vec<u8> = vec![];
+        let cd_write_size = cd.write(&mut out).unwrap();
+        assert_eq!(cd_write_size as usize, out.len());
+
+        let mut reread = Cursor::new(out);
+        let output_cd = ZipCDEntry::read_and_generate_from_signature(&mut reread).unwrap();
+        assert_eq!(output_cd.effective_compressed_size(), SIZE_OVER_4GIB);
+        assert_eq!(output_cd.effective_uncompressed_size(), SIZE_OVER_4GIB + 1);
+        assert_eq!(output_cd.effective_local_header_position(), SIZE_OVER_4GIB + 2);
+    }
+
+    #[test_case]
+    fn zip64_local_file_header_write_test() {
+        let mut zip_file = Cursor::new(include_bytes!("./assets/explicit_utf-8.zip"));
+        let mut local_header = ZipLocalFileHeader::read_and_generate_from_signature(&mut zip_file).unwrap();
+        let data_size = local_header.compressed_data.len() as u64;
+        local_header.set_sizes(data_size, SIZE_OVER_4GIB).unwrap();
+
+        assert_eq!(local_header.compressed_size, u32::MAX);
+        assert_eq!(local_header.uncompressed_size, u32::MAX);
+
+        let mut out: Vec<u8> = vec![];
+        let local_header_write_size = local_header.write(&mut out).unwrap();
+        assert_eq!(local_header_write_size as usize, out.len());
+
+        let mut reread = Cursor::new(out);
+        let output_local_header = ZipLocalFileHeader::read_and_generate_from_signature(&mut reread).unwrap();
+        assert_eq!(output_local_header.effective_compressed_size(), data_size);
+        assert_eq!(output_local_header.effective_uncompressed_size(), SIZE_OVER_4GIB);
+        assert_eq!(output_local_header.compressed_data, local_header.compressed_data);
+    }
+
+    #[test_case]
+    fn zip64_extra_field_too_long_test() {
+        // an extra field of 65535 bytes (a record with 65531 bytes of data) leaves no room for ZIP64 extended information
+        let mut extra_field: Vec<u8> = vec![0xfe, 0xca, 0xfb, 0xff];
+        extra_field.resize(u16::MAX as usize, 0);
+
+        let mut zip_file = Cursor::new(include_bytes!("./assets/explicit_utf-8.zip"));
+        let mut local_header = ZipLocalFileHeader::read_and_generate_from_signature(&mut zip_file).unwrap();
+        assert!(local_header.set_extra_field_from_slice(&[extra_field.as_slice(), &[0]].concat()).is_err());
+        local_header.set_extra_field_from_slice(&extra_field).unwrap();
+        let compressed_size = local_header.compressed_size;
+        assert!(local_header.set_sizes(SIZE_OVER_4GIB, SIZE_OVER_4GIB).is_err());
+        assert_eq!(local_header.compressed_size, compressed_size);
+        assert_eq!(local_header.extra_field_length, u16::MAX);
+        assert_eq!(local_header.extra_field, extra_field);
+
+        zip_file.set_position(0);
+        let eocd = ZipEOCD::from_reader(&mut zip_file).unwrap();
+        let mut cd = ZipCDEntry::all_from_eocd(&mut zip_file, &eocd).unwrap().pop().unwrap();
+        cd.set_extra_field_from_slice(&extra_field).unwrap();
+        assert!(cd.set_local_header_position(SIZE_OVER_4GIB).is_err());
+        assert_eq!(cd.local_header_position, 0);
+        assert_eq!(cd.extra_field_length, u16::MAX);
+        assert!(ZipCDEntry::from_local_file_header(&local_header, 0).is_ok());
+    }
+
+    #[test_case]
+    fn zip64_eocd_write_test() {
+        // More than 65535 entries
+        let eocd = ZipEOCD::new(70000, 0, 0);
+        assert!(eocd.zip64.is_some());
+        assert_eq!(eocd.n_cd_entries, u16::MAX);
+
+        let mut out: Vec<u8> = vec![];
+        eocd.write(&mut out).unwrap();
+        assert_eq!(eocd.starting_position_with_signature as usize + 22, out.len());
+
+        let mut reread = Cursor::new(out);
+        let output_eocd = ZipEOCD::from_reader(&mut reread).unwrap();
+        assert!(output_eocd.check_unsupported_zip_type().is_ok());
+        assert_eq!(output_eocd.effective_n_cd_entries(), 70000);
+        assert_eq!(output_eocd.zip64_locator.unwrap().zip64_eocd_position, 0);
+    }
+
+    #[test_case]
+    fn zip64_streamed_entry_write_test() {
+        let chunk = vec![0u8; 1 << 20];
+        // 4 GiB + 1 MiB
+        let n_chunks = (1 << 12) + 1;
+        let options = ZipEntryOptions::new().compression_method(COMPRESSION_METHOD_STORED);
+
+        // fails before the crossing chunk is written
+        let mut writer = ZipArchiveWriter::new(CountingSink::new());
+        let mut entry = writer.start_file(b"large.bin", &options).unwrap();
+        let error = (0..n_chunks)
+            .map(|_| entry.write_all(&chunk))
+            .find(|result| result.is_err())
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        drop(entry);
+        let sink = writer.finish().unwrap();
+        assert!(sink.n_written < u32::MAX as u64);
+
+        let mut writer = ZipArchiveWriter::new(CountingSink::new());
+        let mut entry = writer
+            .start_file(b"large.bin", &options.clone().large_file(true))
+            .unwrap();
+        for _ in 0..n_chunks {
+            entry.write_all(&chunk).unwrap();
+        }
+        entry.finish().unwrap();
+        let sink = writer.finish().unwrap();
+        let size = (n_chunks as u64) << 20;
+        assert!(sink.n_written > size);
+
+        // the sizes in the local file header refer to ZIP64 extended information with zero sizes
+        let local_header = ZipLocalFileHeader::read_header_from_signature(&mut Cursor::new(sink.head.as_slice())).unwrap();
+        assert_eq!(local_header.compressed_size, u32::MAX);
+        assert_eq!(local_header.uncompressed_size, u32::MAX);
+        let zip64 = local_header.zip64_extended_information().unwrap().unwrap();
+        assert_eq!(zip64.compressed_size, Some(0));
+        assert_eq!(zip64.uncompressed_size, Some(0));
+
+        // the content is followed by the ZIP64 data descriptor and the central directory
+        let descriptor_position = sink.tail.windows(4).position(|window| window == b"PK\x07\x08").unwrap();
+        let descriptor = &sink.tail[descriptor_position..descriptor_position + 24];
+        assert_eq!(&descriptor[8..16], &size.to_le_bytes());
+        assert_eq!(&descriptor[16..24], &size.to_le_bytes());
+        let mut cd_bytes = Cursor::new(&sink.tail[descriptor_position + 24..]);
+        let cd = ZipCDEntry::read_and_generate_from_signature(&mut cd_bytes).unwrap();
+        assert_eq!(&descriptor[4..8], &cd.crc32.to_le_bytes());
+        assert_eq!(cd.effective_compressed_size(), size);
+        assert_eq!(cd.effective_uncompressed_size(), size);
+    }
+
+    #[test_case]
+    fn zip64_streamed_small_entry_test() {
+        static FILE_CONTENT: &[u8] = b"ZIP64 data descriptor";
+        let options = ZipEntryOptions::new().large_file(true);
+        let mut writer = ZipArchiveWriter::new(Vec::new());
+        let mut entry = writer.start_file(b"small.txt", &options).unwrap();
+        entry.write_all(FILE_CONTENT).unwrap();
+        entry.finish().unwrap();
+        let zip_bytes = writer.finish().unwrap();
+
+        let local_header = ZipLocalFileHeader::read_forward_only(&mut zip_bytes.as_slice(), 0).unwrap();
+        let data_descriptor = local_header.data_descriptor.unwrap();
+        assert!(data_descriptor.is_zip64);
+        assert_eq!(data_descriptor.uncompressed_size, FILE_CONTENT.len() as u64);
+
+        let archive = ZipArchive::new(Cursor::new(zip_bytes.as_slice())).unwrap();
+        assert_eq!(archive.by_index(0).unwrap().decompress().unwrap(), FILE_CONTENT);
+    }
+
+}
@@ -0,0 +1,78 @@
+#![feature(core_intrinsics,lang_items,start,default_alloc_error_handler,custom_test_frameworks)]
+#![no_std]
+#![no_main]
+#![test_runner(test_runner)]
+#![reexport_test_harness_main = "test_main"]
+
+
+pub fn test_runner(tests: &[&dyn Fn()]) {
+
+    for test in tests {
+        test();
+    }
+
+}
+
+#[cfg(test)]
+#[start]
+#[no_mangle]
+pub extern "C" fn _start() {
+    test_main();
+}
+
+
+#[cfg(test)]
+    mod test {
+
+    use zip_structs::zip_archive::ZipArchive;
+    use zip_structs::zip_archive_editor::ZipArchiveEditor;
+    use zip_structs::zip_archive_writer::ZipEntryOptions;
+
+    extern crate  alloc;
+    use alloc::vec::Vec;
+
+    use core::include_bytes;
+    use core2::io::Cursor;
+
+    #[test_case]
+    fn zip_archive_editor_test() {
+        let zip_bytes: &[u8] = include_bytes!("./assets/childrens-literature.epub");
+        let source = ZipArchive::new(Cursor::new(zip_bytes)).unwrap();
+        let names: Vec<Vec<u8>> = source.entries().map(|entry| entry.file_name_raw().to_vec()).collect();
+
+        let mut editor = ZipArchiveEditor::new(Cursor::new(zip_bytes)).unwrap();
+        assert!(editor.rename(&names[1], b"META-INF/renamed.xml"));
+        assert!(editor.remove(&names[2]));
+        assert!(editor.replace(&names[3], b"replaced".to_vec(), &ZipEntryOptions::new()));
+        assert!(editor.add_file(b"added.txt", b"added".to_vec(), &ZipEntryOptions::new()));
+        assert!(!editor.remove(b"not_found"));
+        // names already in use are rejected
+        assert!(!editor.rename(&names[4], b"META-INF/renamed.xml"));
+        assert!(!editor.add_file(&names[4], b"duplicate".to_vec(), &ZipEntryOptions::new()));
+        assert!(!editor.add_file(b"added.txt", b"duplicate".to_vec(), &ZipEntryOptions::new()));
+        assert!(editor.add_directory(b"added", &ZipEntryOptions::new()));
+        assert!(!editor.add_directory(b"added/", &ZipEntryOptions::new()));
+        assert!(editor.remove(b"added/"));
+        assert_eq!(editor.len(), names.len());
+        let output = editor.write_to(Vec::new()).unwrap();
+
+        let archive = ZipArchive::new(Cursor::new(output.as_slice())).unwrap();
+        assert_eq!(archive.len(), names.len());
+        assert_eq!(
+            archive.by_name(b"META-INF/renamed.xml").unwrap().decompress().unwrap(),
+            source.by_name(&names[1]).unwrap().decompress().unwrap()
+        );
+        assert!(archive.by_name(&names[2]).is_none());
+        assert_eq!(archive.by_name(&names[3]).unwrap().decompress().unwrap(), b"replaced");
+        assert_eq!(archive.by_name(b"added.txt").unwrap().decompress().unwrap(), b"added");
+
+        // untouched entries are copied verbatim
+        for name in &names[4..] {
+            let source_entry = source.by_name(name).unwrap().local_file_header().unwrap();
+            let entry = archive.by_name(name).unwrap().local_file_header().unwrap();
+            assert_eq!(entry.compressed_data, source_entry.compressed_data);
+            assert_eq!(entry.crc32, source_entry.crc32);
+        }
+    }
+
+}
@@ -0,0 +1,112 @@
+#![feature(core_intrinsics,lang_items,start,default_alloc_error_handler,custom_test_frameworks)]
+#![no_std]
+#![no_main]
+#![test_runner(test_runner)]
+#![reexport_test_harness_main = "test_main"]
+
+
+pub fn test_runner(tests: &[&dyn Fn()]) {
+
+    for test in tests {
+        test();
+    }
+
+}
+
+#[cfg(test)]
+#[start]
+#[no_mangle]
+pub extern "C" fn _start() {
+    test_main();
+}
+
+
+#[cfg(test)]
+    mod test {
+
+    use zip_structs::zip_archive::ZipArchive;
+
+    extern crate  alloc;
+
+    use core::include_bytes;
+    use core2::io::Cursor;
+
+    static EXPECTED_FILE_NAME_UTF8: &[u8] = "テスト.txt".as_bytes();
+    static EXPECTED_FILE_CONTENT: &[u8] = "テスト".as_bytes();
+
+    #[test_case]
+    fn zip_archive_by_name_test() {
+        let archive = ZipArchive::new(Cursor::new(include_bytes!("./assets/explicit_utf-8.zip"))).unwrap();
+        assert_eq!(archive.len(), 1);
+        assert!(!archive.is_empty());
+
+        let entry = archive.by_name(EXPECTED_FILE_NAME_UTF8).unwrap();
+        assert_eq!(entry.index(), 0);
+        assert_eq!(entry.decompress().unwrap().as_slice(), EXPECTED_FILE_CONTENT);
+        assert!(archive.by_name(b"not_found.txt").is_none());
+        assert!(archive.by_index(1).is_none());
+    }
+
+    #[test_case]
+    fn zip_archive_entries_test() {
+        let archive = ZipArchive::new(Cursor::new(include_bytes!("./assets/childrens-literature.epub"))).unwrap();
+        assert_eq!(archive.entries().count(), archive.len());
+
+        for entry in archive.entries() {
+            let local_file_header = entry.local_file_header().unwrap();
+            assert_eq!(local_file_header.file_name_raw.as_slice(), entry.file_name_raw());
+        }
+
+        let container = archive.by_name(b"META-INF/container.xml").unwrap();
+        assert!(container.decompress().unwrap().starts_with(b"<?xml"));
+    }
+
+    #[test_case]
+    fn name_index_test() {
+        let archive = ZipArchive::new(Cursor::new(include_bytes!("./assets/childrens-literature.epub"))).unwrap();
+        let name_index = archive.name_index();
+        assert_eq!(name_index.len(), archive.len());
+
+        assert_eq!(name_index.find(b"mimetype"), Some(0));
+        assert!(name_index.find(b"meta-inf/CONTAINER.XML").is_none());
+        assert_eq!(
+            name_index.find_case_insensitive(b"meta-inf/CONTAINER.XML"),
+            name_index.find(b"META-INF/container.xml")
+        );
+        assert!(archive.by_name_case_insensitive(b"EPUB/PACKAGE.OPF").is_some());
+
+        // EPUB/css/*.css and EPUB/images/cover.png are not directly in EPUB/
+        let epub_files = archive.list_directory(b"EPUB");
+        assert_eq!(epub_files.len(), 5);
+        assert!(epub_files.iter().all(|entry| entry.file_name_raw().starts_with(b"EPUB/")));
+
+        let css_files = archive.by_prefix(b"EPUB/css/");
+        assert_eq!(css_files.len(), 2);
+        assert_eq!(css_files[0].file_name_raw(), b"EPUB/css/epub.css");
+
+        let root_files = archive.list_directory(b"");
+        assert_eq!(root_files.len(), 1);
+        assert_eq!(root_files[0].file_name_raw(), b"mimetype");
+    }
+
+    #[test_case]
+    fn streaming_read_test() {
+        let bytes = include_bytes!("./assets/childrens-literature.epub");
+        let archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let entry = archive.by_name(b"META-INF/container.xml").unwrap();
+
+        let local_file_header = entry.local_file_header_without_data().unwrap();
+        assert!(local_file_header.compressed_data.is_empty());
+        let data_range = local_file_header.data_range();
+        assert_eq!(
+            &bytes[data_range.start as usize..data_range.end as usize],
+            &*entry.local_file_header().unwrap().compressed_data
+        );
+
+        let streamed = entry
+            .with_decompressed_reader(|reader| reader.read_all_decompressed(0))
+            .unwrap();
+        assert_eq!(streamed, entry.decompress().unwrap());
+    }
+
+}
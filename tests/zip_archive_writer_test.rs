@@ -0,0 +1,532 @@
+#![feature(core_intrinsics,lang_items,start,default_alloc_error_handler,custom_test_frameworks)]
+#![no_std]
+#![no_main]
+#![test_runner(test_runner)]
+#![reexport_test_harness_main = "test_main"]
+
+
+pub fn test_runner(tests: &[&dyn Fn()]) {
+
+    for test in tests {
+        test();
+    }
+
+}
+
+#[cfg(test)]
+#[start]
+#[no_mangle]
+pub extern "C" fn _start() {
+    test_main();
+}
+
+
+#[cfg(test)]
+    mod test {
+
+    use zip_structs::zip_archive::ZipArchive;
+    use zip_structs::zip_archive_writer::{ZipArchiveWriter, ZipEntryOptions};
+    use zip_structs::zip_compression::{
+        compress, COMPRESSION_METHOD_BZIP2, COMPRESSION_METHOD_DEFLATE, COMPRESSION_METHOD_LZMA,
+        COMPRESSION_METHOD_STORED, COMPRESSION_METHOD_XZ, COMPRESSION_METHOD_ZSTD,
+    };
+    use zip_structs::zip_local_file_header::ZipLocalFileHeader;
+
+    extern crate  alloc;
+    use alloc::vec::Vec;
+
+    use core2::io::{Cursor, Write};
+
+    static FILE_NAME: &[u8] = "テスト.txt".as_bytes();
+    static FILE_CONTENT: &[u8] = "テスト".as_bytes();
+
+    #[test_case]
+    fn zip_archive_writer_test() {
+        let mut writer = ZipArchiveWriter::new(Vec::new());
+        writer.add_directory(b"docs", &ZipEntryOptions::new()).unwrap();
+        writer
+            .add_file(
+                FILE_NAME,
+                FILE_CONTENT,
+                &ZipEntryOptions::new().file_comment(b"comment".to_vec()),
+            )
+            .unwrap();
+        writer.add_file(b"docs/empty.txt", b"", &ZipEntryOptions::default()).unwrap();
+        writer.set_comment_from_slice(b"archive comment");
+        assert_eq!(writer.len(), 3);
+        let zip_bytes = writer.finish().unwrap();
+
+        let archive = ZipArchive::new(Cursor::new(zip_bytes)).unwrap();
+        assert_eq!(archive.len(), 3);
+        assert_eq!(archive.eocd().comment.as_slice(), b"archive comment");
+
+        let directory = archive.by_index(0).unwrap();
+        assert_eq!(directory.file_name_raw(), b"docs/");
+        assert_eq!(directory.central_directory().external_file_attributes, 0x10);
+
+        let file = archive.by_name(FILE_NAME).unwrap();
+        assert!(file.central_directory().is_encoded_in_utf8());
+        assert_eq!(file.central_directory().file_comment.as_slice(), b"comment");
+        assert_eq!(file.decompress().unwrap().as_slice(), FILE_CONTENT);
+
+        assert_eq!(archive.list_directory(b"docs").len(), 1);
+    }
+
+    #[test_case]
+    fn zip_archive_writer_deflate_test() {
+        let data: Vec<u8> = FILE_CONTENT.iter().cycle().take(10000).cloned().collect();
+        let mut writer = ZipArchiveWriter::new(Vec::new());
+        writer.add_file(b"default.txt", &data, &ZipEntryOptions::new()).unwrap();
+        writer.add_file(b"level0.txt", &data, &ZipEntryOptions::new().compression_level(0)).unwrap();
+        writer
+            .add_file(
+                b"stored.txt",
+                &data,
+                &ZipEntryOptions::new().compression_method(COMPRESSION_METHOD_STORED),
+            )
+            .unwrap();
+        assert!(writer
+            .add_file(b"invalid.txt", &data, &ZipEntryOptions::new().compression_level(10))
+            .is_err());
+        let zip_bytes = writer.finish().unwrap();
+
+        let archive = ZipArchive::new(Cursor::new(zip_bytes)).unwrap();
+        assert_eq!(archive.len(), 3);
+        for entry in archive.entries() {
+            assert_eq!(entry.decompress().unwrap(), data);
+        }
+        let default_cd = archive.by_name(b"default.txt").unwrap().central_directory();
+        assert_eq!(default_cd.compression_method, COMPRESSION_METHOD_DEFLATE);
+        assert!((default_cd.compressed_size as usize) < data.len());
+        assert_eq!(default_cd.uncompressed_size as usize, data.len());
+        let stored_cd = archive.by_name(b"stored.txt").unwrap().central_directory();
+        assert_eq!(stored_cd.compressed_size as usize, data.len());
+
+        // words in a pseudo-random order: a higher level finds more matches
+        let words: [&[u8]; 6] = [b"alpha ", b"beta ", b"gamma ", b"delta ", b"epsilon ", b"zeta "];
+        let mut seed: u32 = 1;
+        let text: Vec<u8> = (0..20000)
+            .flat_map(|_| {
+                seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+                words[(seed >> 16) as usize % words.len()].iter().cloned()
+            })
+            .collect();
+        let fast = compress(&text, COMPRESSION_METHOD_DEFLATE, 1).unwrap();
+        let best = compress(&text, COMPRESSION_METHOD_DEFLATE, 9).unwrap();
+        assert!(best.len() < fast.len());
+    }
+
+    #[test_case]
+    fn zip_archive_writer_streaming_test() {
+        let mut writer = ZipArchiveWriter::new(Vec::new());
+        let mut entry = writer.start_file(FILE_NAME, &ZipEntryOptions::new()).unwrap();
+        for _ in 0..100 {
+            entry.write_all(FILE_CONTENT).unwrap();
+        }
+        entry.finish().unwrap();
+        let zip_bytes = writer.finish().unwrap();
+
+        let archive = ZipArchive::new(Cursor::new(zip_bytes.as_slice())).unwrap();
+        let cd = archive.by_index(0).unwrap().central_directory();
+        assert_ne!(cd.general_purpose_flags & 0x0008, 0);
+        assert_eq!(cd.uncompressed_size as usize, FILE_CONTENT.len() * 100);
+
+        let local_file_header = archive.by_index(0).unwrap().local_file_header_without_data().unwrap();
+        assert_eq!(local_file_header.crc32, 0);
+        assert_eq!(local_file_header.compressed_size, 0);
+        assert_eq!(local_file_header.uncompressed_size, 0);
+
+        // data descriptor with the signature just after the content
+        let data_end = (local_file_header.data_starting_position() + cd.compressed_size as u64) as usize;
+        assert_eq!(&zip_bytes[data_end..data_end + 4], b"PK\x07\x08");
+        assert_eq!(&zip_bytes[data_end + 4..data_end + 8], &cd.crc32.to_le_bytes());
+    }
+
+    #[test_case]
+    fn zip_archive_writer_streaming_read_test() {
+        let mut writer = ZipArchiveWriter::new(Vec::new());
+        for has_signature in [true, false] {
+            let options = ZipEntryOptions::new().data_descriptor_signature(has_signature);
+            let mut entry = writer.start_file(FILE_NAME, &options).unwrap();
+            for _ in 0..100 {
+                entry.write_all(FILE_CONTENT).unwrap();
+            }
+            entry.finish().unwrap();
+        }
+        let zip_bytes = writer.finish().unwrap();
+        let expected = FILE_CONTENT.repeat(100);
+
+        // sizes are taken from the central directory
+        let archive = ZipArchive::new(Cursor::new(zip_bytes.as_slice())).unwrap();
+        for entry in archive.entries() {
+            assert_eq!(entry.decompress().unwrap(), expected);
+        }
+
+        // the end of the content is located by decoding it
+        let mut read: &[u8] = zip_bytes.as_slice();
+        for has_signature in [true, false] {
+            let position = (zip_bytes.len() - read.len()) as u64;
+            let local_file_header = ZipLocalFileHeader::read_forward_only(&mut read, position).unwrap();
+            assert_eq!(local_file_header.starting_position_with_signature, position);
+            let data_descriptor = local_file_header.data_descriptor.as_ref().unwrap();
+            assert_eq!(data_descriptor.has_signature, has_signature);
+            assert_eq!(data_descriptor.uncompressed_size as usize, expected.len());
+            assert_eq!(local_file_header.decompress().unwrap(), expected);
+        }
+        // central directory follows
+        assert_eq!(&read[..4], b"PK\x01\x02");
+    }
+
+    /// `BufRead` object that returns at most `chunk_size` bytes at a time
+    struct ChunkedRead<'a> {
+        bytes: &'a [u8],
+        chunk_size: usize,
+    }
+
+    impl core2::io::Read for ChunkedRead<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> core2::io::Result<usize> {
+            let n_read = core::cmp::min(buf.len(), core::cmp::min(self.chunk_size, self.bytes.len()));
+            buf[..n_read].copy_from_slice(&self.bytes[..n_read]);
+            self.bytes = &self.bytes[n_read..];
+            return Ok(n_read);
+        }
+    }
+
+    impl core2::io::BufRead for ChunkedRead<'_> {
+        fn fill_buf(&mut self) -> core2::io::Result<&[u8]> {
+            return Ok(&self.bytes[..core::cmp::min(self.chunk_size, self.bytes.len())]);
+        }
+
+        fn consume(&mut self, amt: usize) {
+            self.bytes = &self.bytes[amt..];
+        }
+    }
+
+    #[test_case]
+    fn zip_archive_writer_streaming_read_buffered_test() {
+        use zip_structs::zip_stream_reader::ZipStreamReader;
+
+        let mut writer = ZipArchiveWriter::new(Vec::new());
+        for has_signature in [true, false] {
+            let options = ZipEntryOptions::new().data_descriptor_signature(has_signature);
+            let mut entry = writer.start_file(FILE_NAME, &options).unwrap();
+            for _ in 0..1000 {
+                entry.write_all(FILE_CONTENT).unwrap();
+            }
+            entry.finish().unwrap();
+        }
+        let zip_bytes = writer.finish().unwrap();
+        let expected = FILE_CONTENT.repeat(1000);
+        let cd_starting_position = ZipArchive::new(Cursor::new(zip_bytes.as_slice()))
+            .unwrap()
+            .eocd()
+            .effective_cd_starting_position() as usize;
+
+        // nothing after each entry is consumed from the buffer, wherever the buffer boundaries are
+        for chunk_size in [1, 3, 4096] {
+            let mut stream_reader = ZipStreamReader::new(ChunkedRead {
+                bytes: zip_bytes.as_slice(),
+                chunk_size,
+            });
+            for _ in 0..2 {
+                let local_file_header = stream_reader.next_entry().unwrap().unwrap();
+                assert_eq!(local_file_header.decompress().unwrap(), expected);
+            }
+            assert!(stream_reader.next_entry().unwrap().is_none());
+            assert_eq!(stream_reader.position() as usize, cd_starting_position + 4);
+            assert_eq!(stream_reader.into_inner().bytes, &zip_bytes[cd_starting_position + 4..]);
+        }
+
+        // a seekable reader moves back by the bytes read ahead
+        let mut zip_file = Cursor::new(zip_bytes.as_slice());
+        for _ in 0..2 {
+            let local_file_header = ZipLocalFileHeader::read_and_generate_from_signature(&mut zip_file).unwrap();
+            assert_eq!(local_file_header.decompress().unwrap(), expected);
+        }
+        assert_eq!(zip_file.position() as usize, cd_starting_position);
+    }
+
+    #[test_case]
+    fn zip_archive_writer_streaming_read_all_methods_test() {
+        use zip_structs::zip_stream_reader::ZipStreamReader;
+
+        let compression_methods = [
+            COMPRESSION_METHOD_STORED,
+            COMPRESSION_METHOD_DEFLATE,
+            COMPRESSION_METHOD_BZIP2,
+            COMPRESSION_METHOD_LZMA,
+            COMPRESSION_METHOD_ZSTD,
+            COMPRESSION_METHOD_XZ,
+        ];
+        let expected = FILE_CONTENT.repeat(1000);
+        let mut writer = ZipArchiveWriter::new(Vec::new());
+        for compression_method in compression_methods {
+            for has_signature in [true, false] {
+                let options = ZipEntryOptions::new()
+                    .compression_method(compression_method)
+                    .data_descriptor_signature(has_signature);
+                let mut entry = writer.start_file(FILE_NAME, &options).unwrap();
+                entry.write_all(&expected).unwrap();
+                entry.finish().unwrap();
+            }
+        }
+        // encrypted content cannot be decoded; it is located by the data descriptor alone
+        let options = ZipEntryOptions::new().aes_encryption(b"password", 3, b"0123456789abcdef");
+        let mut entry = writer.start_file(FILE_NAME, &options).unwrap();
+        entry.write_all(&expected).unwrap();
+        entry.finish().unwrap();
+        let zip_bytes = writer.finish().unwrap();
+
+        let local_file_headers = ZipStreamReader::new(zip_bytes.as_slice())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(local_file_headers.len(), compression_methods.len() * 2 + 1);
+        let expected_compression_methods = compression_methods.iter().flat_map(|&method| [method, method]);
+        for (local_file_header, compression_method) in local_file_headers.iter().zip(expected_compression_methods) {
+            assert_eq!(local_file_header.compression_method, compression_method);
+            assert_eq!(local_file_header.decompress().unwrap(), expected);
+        }
+        let encrypted = local_file_headers.last().unwrap();
+        assert_eq!(encrypted.decompress_with_password(b"password").unwrap(), expected);
+    }
+
+    #[test_case]
+    fn zip_archive_writer_streaming_invalid_size_test() {
+        use zip_structs::zip_error::ZipReadError;
+
+        let mut writer = ZipArchiveWriter::new(Vec::new());
+        let mut entry = writer.start_file(FILE_NAME, &ZipEntryOptions::new()).unwrap();
+        entry.write_all(FILE_CONTENT).unwrap();
+        entry.finish().unwrap();
+        let zip_bytes = writer.finish().unwrap();
+
+        // the size in the central directory is used for the entry with a data descriptor, and must fit in the archive
+        let archive = ZipArchive::new(Cursor::new(zip_bytes.as_slice())).unwrap();
+        let mut cd = archive.by_index(0).unwrap().central_directory().clone();
+        cd.compressed_size = u32::MAX - 1;
+        assert!(matches!(
+            ZipLocalFileHeader::from_central_directory(&mut Cursor::new(zip_bytes.as_slice()), &cd),
+            Err(ZipReadError::InvalidZipArchive { .. })
+        ));
+    }
+
+    /// Growable in-memory file (`Cursor<Vec<u8>>` of core2 does not implement `Write`)
+    struct VecFile {
+        data: Vec<u8>,
+        position: usize,
+    }
+
+    impl core2::io::Read for VecFile {
+        fn read(&mut self, buf: &mut [u8]) -> core2::io::Result<usize> {
+            let n_read = core::cmp::min(buf.len(), self.data.len().saturating_sub(self.position));
+            buf[..n_read].copy_from_slice(&self.data[self.position..self.position + n_read]);
+            self.position += n_read;
+            return Ok(n_read);
+        }
+    }
+
+    impl Write for VecFile {
+        fn write(&mut self, buf: &[u8]) -> core2::io::Result<usize> {
+            let end = self.position + buf.len();
+            if self.data.len() < end {
+                self.data.resize(end, 0);
+            }
+            self.data[self.position..end].copy_from_slice(buf);
+            self.position = end;
+            return Ok(buf.len());
+        }
+
+        fn flush(&mut self) -> core2::io::Result<()> {
+            return Ok(());
+        }
+    }
+
+    impl core2::io::Seek for VecFile {
+        fn seek(&mut self, pos: core2::io::SeekFrom) -> core2::io::Result<u64> {
+            self.position = match pos {
+                core2::io::SeekFrom::Start(position) => position as usize,
+                core2::io::SeekFrom::End(offset) => (self.data.len() as i64 + offset) as usize,
+                core2::io::SeekFrom::Current(offset) => (self.position as i64 + offset) as usize,
+            };
+            return Ok(self.position as u64);
+        }
+    }
+
+    #[test_case]
+    fn zip_archive_writer_append_test() {
+        let mut writer = ZipArchiveWriter::new(Vec::new());
+        writer.set_comment_from_slice(b"comment");
+        writer.add_file(FILE_NAME, FILE_CONTENT, &ZipEntryOptions::new()).unwrap();
+        let zip_bytes = writer.finish().unwrap();
+        let cd_starting_position = ZipArchive::new(Cursor::new(zip_bytes.as_slice()))
+            .unwrap()
+            .eocd()
+            .effective_cd_starting_position() as usize;
+
+        let mut writer = ZipArchiveWriter::append(VecFile {
+            data: zip_bytes.clone(),
+            position: 0,
+        })
+        .unwrap();
+        assert_eq!(writer.len(), 1);
+        writer.add_file(b"appended.txt", b"appended", &ZipEntryOptions::new()).unwrap();
+        let appended_bytes = writer.finish().unwrap().data;
+
+        // the existing entry is kept as it is
+        assert_eq!(&appended_bytes[..cd_starting_position], &zip_bytes[..cd_starting_position]);
+        let archive = ZipArchive::new(Cursor::new(appended_bytes.as_slice())).unwrap();
+        assert_eq!(archive.len(), 2);
+        assert_eq!(archive.eocd().comment, b"comment");
+        assert_eq!(archive.by_name(FILE_NAME).unwrap().decompress().unwrap(), FILE_CONTENT);
+        assert_eq!(archive.by_name(b"appended.txt").unwrap().decompress().unwrap(), b"appended");
+    }
+
+    #[test_case]
+    fn zip_archive_writer_aes_test() {
+        use zip_structs::zip_error::ZipReadError;
+
+        let mut writer = ZipArchiveWriter::new(Vec::new());
+        for (strength, salt) in [(1, &b"saltsalt"[..]), (2, b"saltsaltsalt"), (3, b"saltsaltsaltsalt")] {
+            let options = ZipEntryOptions::new().aes_encryption(b"password", strength, salt);
+            writer.add_file(&[b'0' + strength], FILE_CONTENT, &options).unwrap();
+        }
+        // a salt must not be reused with the same password
+        let options = ZipEntryOptions::new()
+            .compression_method(COMPRESSION_METHOD_STORED)
+            .aes_encryption(b"password", 3, b"SALTSALTSALTSALT");
+        let mut entry = writer.start_file(FILE_NAME, &options).unwrap();
+        entry.write_all(FILE_CONTENT).unwrap();
+        entry.finish().unwrap();
+        let invalid_salt = ZipEntryOptions::new().aes_encryption(b"password", 3, b"salt");
+        assert!(writer.add_file(b"invalid", FILE_CONTENT, &invalid_salt).is_err());
+        // an extra field of 65535 bytes leaves no room for WinZip AES extra field
+        let mut extra_field: Vec<u8> = [0xfe, 0xca, 0xfb, 0xff].to_vec();
+        extra_field.resize(u16::MAX as usize, 0);
+        let too_long_extra_field = options.clone().extra_field(extra_field);
+        for result in [
+            writer.add_file(b"invalid", FILE_CONTENT, &too_long_extra_field).err(),
+            writer.start_file(b"invalid", &too_long_extra_field).err(),
+        ] {
+            assert_eq!(result.unwrap().kind(), core2::io::ErrorKind::InvalidInput);
+        }
+        let zip_bytes = writer.finish().unwrap();
+
+        let archive = ZipArchive::new(Cursor::new(zip_bytes.as_slice())).unwrap();
+        assert_eq!(archive.len(), 4);
+        for entry in archive.entries() {
+            assert_eq!(entry.decompress_with_password(b"password").unwrap(), FILE_CONTENT);
+            assert!(matches!(entry.decompress_with_password(b"wrong password"), Err(ZipReadError::InvalidPassword)));
+        }
+    }
+
+    #[test_case]
+    fn zip_archive_writer_bzip2_test() {
+        let large_content = FILE_CONTENT.repeat(1000);
+        let options = ZipEntryOptions::new().compression_method(COMPRESSION_METHOD_BZIP2);
+
+        let mut writer = ZipArchiveWriter::new(Vec::new());
+        writer.add_file(FILE_NAME, &large_content, &options).unwrap();
+        let mut entry = writer.start_file(b"streamed.txt", &options.clone().compression_level(1)).unwrap();
+        entry.write_all(&large_content).unwrap();
+        entry.finish().unwrap();
+        let zip_bytes = writer.finish().unwrap();
+
+        let archive = ZipArchive::new(Cursor::new(zip_bytes)).unwrap();
+        for entry in archive.entries() {
+            assert_eq!(entry.central_directory().compression_method, COMPRESSION_METHOD_BZIP2);
+            assert_eq!(entry.central_directory().version_required_to_extract, 46);
+            assert!(entry.central_directory().compressed_size < large_content.len() as u32);
+            assert_eq!(entry.decompress().unwrap(), large_content);
+        }
+    }
+
+    #[test_case]
+    fn zip_archive_writer_lzma_xz_test() {
+        let large_content = FILE_CONTENT.repeat(1000);
+
+        for compression_method in [COMPRESSION_METHOD_LZMA, COMPRESSION_METHOD_XZ].iter() {
+            let options = ZipEntryOptions::new().compression_method(*compression_method);
+            let mut writer = ZipArchiveWriter::new(Vec::new());
+            writer.add_file(FILE_NAME, &large_content, &options).unwrap();
+            let mut entry = writer.start_file(b"streamed.txt", &options.clone().compression_level(1)).unwrap();
+            entry.write_all(&large_content).unwrap();
+            entry.finish().unwrap();
+            let zip_bytes = writer.finish().unwrap();
+
+            let archive = ZipArchive::new(Cursor::new(zip_bytes)).unwrap();
+            for entry in archive.entries() {
+                let cd = entry.central_directory();
+                assert_eq!(cd.compression_method, *compression_method);
+                assert_eq!(cd.version_required_to_extract, 63);
+                // LZMA data ends with the end-of-stream marker
+                assert_eq!(cd.general_purpose_flags & 0x0002 != 0, *compression_method == COMPRESSION_METHOD_LZMA);
+                assert!(cd.compressed_size < large_content.len() as u32);
+                assert_eq!(entry.decompress().unwrap(), large_content);
+            }
+        }
+    }
+
+    #[test_case]
+    fn zip_archive_writer_zstd_test() {
+        let large_content = FILE_CONTENT.repeat(1000);
+
+        let options = ZipEntryOptions::new().compression_method(COMPRESSION_METHOD_ZSTD);
+        let mut writer = ZipArchiveWriter::new(Vec::new());
+        writer.add_file(FILE_NAME, &large_content, &options).unwrap();
+        writer.add_file(b"empty.txt", b"", &options).unwrap();
+        let mut entry = writer.start_file(b"raw_blocks.txt", &options.clone().compression_level(0)).unwrap();
+        entry.write_all(&large_content).unwrap();
+        entry.finish().unwrap();
+        let zip_bytes = writer.finish().unwrap();
+
+        let archive = ZipArchive::new(Cursor::new(zip_bytes)).unwrap();
+        let entry = archive.by_name(FILE_NAME).unwrap();
+        assert_eq!(entry.central_directory().compression_method, COMPRESSION_METHOD_ZSTD);
+        assert_eq!(entry.central_directory().version_required_to_extract, 63);
+        assert!(entry.central_directory().compressed_size < large_content.len() as u32 / 10);
+        assert_eq!(entry.decompress().unwrap(), large_content);
+        assert_eq!(archive.by_name(b"empty.txt").unwrap().decompress().unwrap(), b"");
+        // level 0 stores the data in raw blocks
+        let entry = archive.by_name(b"raw_blocks.txt").unwrap();
+        assert!(entry.central_directory().compressed_size > large_content.len() as u32);
+        assert_eq!(entry.decompress().unwrap(), large_content);
+        // ruzstd implements only its fastest level, which levels 1 - 22 map to
+        let mut writer = ZipArchiveWriter::new(Vec::new());
+        for level in [1u8, 3, 22].iter() {
+            writer.add_file(&[b'l', *level], &large_content, &options.clone().compression_level(*level)).unwrap();
+        }
+        let result = writer.start_file(b"level23.txt", &options.clone().compression_level(23));
+        assert_eq!(result.err().unwrap().kind(), core2::io::ErrorKind::InvalidInput);
+        let zip_bytes = writer.finish().unwrap();
+        let archive = ZipArchive::new(Cursor::new(zip_bytes)).unwrap();
+        let fastest = archive.by_name(&[b'l', 1]).unwrap().local_file_header().unwrap().compressed_data;
+        for level in [3u8, 22].iter() {
+            let entry = archive.by_name(&[b'l', *level]).unwrap();
+            assert_eq!(entry.local_file_header().unwrap().compressed_data, fastest);
+            assert_eq!(entry.decompress().unwrap(), large_content);
+        }
+    }
+
+    #[test_case]
+    fn zip_archive_writer_split_test() {
+        let large_content = FILE_CONTENT.repeat(1000);
+
+        let mut writer = ZipArchiveWriter::new_split(1024, |_| Ok(Vec::new())).unwrap();
+        let stored = ZipEntryOptions::new().compression_method(COMPRESSION_METHOD_STORED);
+        writer.add_file(FILE_NAME, &large_content, &stored).unwrap();
+        for i in 0..50u8 {
+            writer.add_file(&[b'a', i], FILE_CONTENT, &ZipEntryOptions::new()).unwrap();
+        }
+        let volumes = writer.finish_volumes().unwrap();
+        assert!(volumes.len() > 9);
+        assert_eq!(&volumes[0][..4], b"PK\x07\x08");
+        assert!(volumes.iter().all(|volume| volume.len() <= 1024));
+
+        let archive = ZipArchive::new_multi_volume(volumes.iter().map(|volume| Cursor::new(volume.as_slice())).collect()).unwrap();
+        assert_eq!(archive.len(), 51);
+        let entry = archive.by_name(FILE_NAME).unwrap();
+        assert_eq!(entry.decompress().unwrap(), large_content);
+        assert_eq!(archive.by_name(&[b'a', 49]).unwrap().decompress().unwrap(), FILE_CONTENT);
+    }
+
+}
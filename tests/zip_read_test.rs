@@ -41,14 +41,17 @@ pub extern "C" fn _start() {
 
 
 
-use zip_structs::zip_central_directory::ZipCDEntry;
-use zip_structs::zip_eocd::ZipEOCD;
-use zip_structs::zip_local_file_header::ZipLocalFileHeader;
+use zip_structs::zip_central_directory::{ZipCDEntry, ZipCDEntryRef, DATA_DESCRIPTOR_EXISTS_FLAG_BIT};
+use zip_structs::zip_eocd::{ZipEOCD, ZipEOCDRef};
+use zip_structs::zip_extra_field::{Zip64ExtendedInformation, ZipExtraFieldBuilder};
+use zip_structs::zip_local_file_header::{ZipDataDescriptor, ZipLocalFileHeader, ZipLocalFileHeaderRef};
+use zip_structs::zip_stream_reader::ZipStreamReader;
 
 extern crate  alloc;
         
 use core::include_bytes;
 use core2::io::Cursor;
+use alloc::vec::Vec;
 
 static EXPECTED_FILE_NAME_UTF8: &[u8] = "テスト.txt".as_bytes();
 static EXPECTED_FILE_CONTENT: &[u8] = "テスト".as_bytes();
@@ -146,4 +149,371 @@ fn legacy_filename_encoding_zip_read_test() {
 
 }
 
-}
\ No newline at end of file
+#[test_case]
+fn zip64_eocd_read_test() {
+    let mut zip_file = Cursor::new(include_bytes!("./assets/zip64.zip"));
+    let eocd = ZipEOCD::from_reader(&mut zip_file).unwrap();
+
+    assert!(eocd.is_zip64());
+    assert!(eocd.check_unsupported_zip_type().is_ok());
+    assert_eq!(eocd.cd_starting_position, u32::MAX);
+    let zip64_locator = eocd.zip64_locator.as_ref().unwrap();
+    assert_eq!(zip64_locator.zip64_eocd_position, 0x8F);
+    assert_eq!(zip64_locator.n_disks, 1);
+    let zip64 = eocd.zip64.as_ref().unwrap();
+    assert_eq!(zip64.starting_position_with_signature, 0x8F);
+    assert_eq!(zip64.n_cd_entries, 1);
+    assert_eq!(zip64.cd_starting_position, 0x48);
+    assert_eq!(eocd.effective_n_cd_entries(), 1);
+    assert_eq!(eocd.effective_cd_size(), 0x47);
+    assert_eq!(eocd.effective_cd_starting_position(), 0x48);
+
+    let cd_list = ZipCDEntry::all_from_eocd(&mut zip_file, &eocd).unwrap();
+    assert_eq!(cd_list.len(), 1);
+    assert_eq!(&cd_list[0].file_name_raw, EXPECTED_FILE_NAME_UTF8);
+
+}
+
+#[test_case]
+fn zip64_eocd_invalid_record_size_test() {
+    use zip_structs::zip_error::ZipReadError;
+    // "size of ZIP64 end of central directory record" that exceeds the locator must be rejected before allocation
+    let mut archive = include_bytes!("./assets/zip64.zip").to_vec();
+    archive[0x8F + 4..0x8F + 12].copy_from_slice(&(u64::MAX - 1).to_le_bytes());
+    assert!(matches!(
+        ZipEOCD::from_reader(&mut Cursor::new(&archive)),
+        Err(ZipReadError::InvalidZipArchive { .. })
+    ));
+    assert!(matches!(ZipEOCDRef::from_bytes(&archive), Err(ZipReadError::InvalidZipArchive { .. })));
+}
+
+#[test_case]
+fn zip64_extra_field_read_test() {
+    let mut zip_file = Cursor::new(include_bytes!("./assets/zip64.zip"));
+    let eocd = ZipEOCD::from_reader(&mut zip_file).unwrap();
+    let mut cd_list = ZipCDEntry::all_from_eocd(&mut zip_file, &eocd).unwrap();
+    let cd = cd_list.pop().unwrap();
+
+    assert_eq!(cd.uncompressed_size, u32::MAX);
+    assert_eq!(
+        cd.effective_uncompressed_size() as usize,
+        EXPECTED_FILE_CONTENT.len()
+    );
+    assert_eq!(
+        cd.effective_compressed_size() as usize,
+        EXPECTED_FILE_CONTENT.len()
+    );
+    assert_eq!(cd.effective_local_header_position(), 0);
+
+    let local_file_header = ZipLocalFileHeader::from_central_directory(&mut zip_file, &cd).unwrap();
+    assert_eq!(local_file_header.compressed_size, u32::MAX);
+    assert_eq!(local_file_header.uncompressed_size, u32::MAX);
+    assert_eq!(
+        local_file_header.effective_compressed_size() as usize,
+        EXPECTED_FILE_CONTENT.len()
+    );
+    assert_eq!(
+        local_file_header.compressed_data.as_ref(),
+        EXPECTED_FILE_CONTENT
+    );
+
+}
+
+#[test_case]
+fn zip64_extra_field_invalid_compressed_size_test() {
+    use zip_structs::zip_error::ZipReadError;
+    // compressed size in ZIP64 extended information extra field of the local file header is much larger than the archive
+    let mut archive = include_bytes!("./assets/zip64.zip").to_vec();
+    archive[55..63].copy_from_slice(&(u64::MAX - 1).to_le_bytes());
+    let mut zip_file = Cursor::new(&archive);
+    let eocd = ZipEOCD::from_reader(&mut zip_file).unwrap();
+    let cd_list = ZipCDEntry::all_from_eocd(&mut zip_file, &eocd).unwrap();
+    assert!(matches!(
+        ZipLocalFileHeader::from_central_directory(&mut zip_file, &cd_list[0]),
+        Err(ZipReadError::InvalidZipArchive { .. })
+    ));
+}
+
+#[test_case]
+fn decompress_test() {
+    let mut zip_file = Cursor::new(include_bytes!("./assets/childrens-literature.epub"));
+    let eocd = ZipEOCD::from_reader(&mut zip_file).unwrap();
+    let cd_list = ZipCDEntry::all_from_eocd(&mut zip_file, &eocd).unwrap();
+
+    // The first entry of EPUB is the stored "mimetype" file
+    let mimetype = ZipLocalFileHeader::from_central_directory(&mut zip_file, &cd_list[0]).unwrap();
+    assert_eq!(mimetype.compression_method, 0);
+    assert_eq!(mimetype.decompress().unwrap().as_slice(), b"application/epub+zip");
+
+    for cd in &cd_list {
+        let local_file_header = ZipLocalFileHeader::from_central_directory(&mut zip_file, cd).unwrap();
+        let content = local_file_header.decompress().unwrap();
+        assert_eq!(content.len() as u64, cd.effective_uncompressed_size());
+    }
+
+}
+
+#[test_case]
+fn crc32_mismatch_test() {
+    use zip_structs::zip_error::ZipReadError;
+
+    let mut zip_bytes = include_bytes!("./assets/explicit_utf-8.zip").to_vec();
+    // Corrupt the first byte of the file content
+    zip_bytes[0x2B] ^= 0xFF;
+    let mut zip_file = Cursor::new(zip_bytes);
+    let local_file_header = ZipLocalFileHeader::read_and_generate_from_signature(&mut zip_file).unwrap();
+
+    match local_file_header.decompress() {
+        Err(ZipReadError::Crc32Mismatch { expected, actual }) => {
+            assert_eq!(expected, local_file_header.crc32);
+            assert_ne!(expected, actual);
+        }
+        _ => panic!("CRC-32 mismatch was not detected"),
+    }
+
+}
+
+#[test_case]
+fn zero_copy_read_test() {
+    let zip_bytes: &[u8] = include_bytes!("./assets/explicit_utf-8.zip");
+    let eocd = ZipEOCDRef::from_bytes(zip_bytes).unwrap();
+    assert_eq!(eocd.n_cd_entries, 1);
+    assert_eq!(eocd.comment.len(), 0);
+
+    let cd_list = ZipCDEntryRef::all_from_eocd(zip_bytes, &eocd).unwrap();
+    assert_eq!(cd_list.len(), 1);
+    assert_eq!(cd_list[0].file_name_raw, EXPECTED_FILE_NAME_UTF8);
+
+    let local_file_header = ZipLocalFileHeaderRef::from_central_directory(zip_bytes, &cd_list[0]).unwrap();
+    assert_eq!(local_file_header.file_name_raw, EXPECTED_FILE_NAME_UTF8);
+    // the file content is borrowed from the input
+    let data_start = local_file_header.compressed_data.as_ptr() as usize - zip_bytes.as_ptr() as usize;
+    assert_eq!(data_start, 30 + local_file_header.file_name_length as usize + local_file_header.extra_field_length as usize);
+    assert_eq!(local_file_header.decompress().unwrap().as_slice(), EXPECTED_FILE_CONTENT);
+}
+
+#[test_case]
+fn data_descriptor_test() {
+    let mut zip_file = Cursor::new(include_bytes!("./assets/explicit_utf-8.zip"));
+    let mut local_file_header = ZipLocalFileHeader::read_and_generate_from_signature(&mut zip_file).unwrap();
+    let crc32 = local_file_header.crc32;
+    let compressed_size = local_file_header.effective_compressed_size();
+    let uncompressed_size = local_file_header.effective_uncompressed_size();
+    local_file_header.general_purpose_flags |= DATA_DESCRIPTOR_EXISTS_FLAG_BIT;
+
+    for has_signature in [true, false] {
+        local_file_header.data_descriptor = Some(ZipDataDescriptor::new(crc32, compressed_size, uncompressed_size, has_signature));
+        let mut out: Vec<u8> = Vec::new();
+        local_file_header.write(&mut out).unwrap();
+        let output_local_header = ZipLocalFileHeader::read_and_generate_from_signature(&mut Cursor::new(out)).unwrap();
+        let data_descriptor = output_local_header.data_descriptor.unwrap();
+        assert_eq!(data_descriptor.has_signature, has_signature);
+        assert!(!data_descriptor.is_zip64);
+        assert_eq!(data_descriptor.crc32, crc32);
+        assert_eq!(data_descriptor.compressed_size, compressed_size);
+        assert_eq!(data_descriptor.uncompressed_size, uncompressed_size);
+    }
+
+    // ZIP64 data descriptor follows a local file header with ZIP64 extended information extra field
+    let zip64 = Zip64ExtendedInformation {
+        uncompressed_size: Some(uncompressed_size),
+        compressed_size: Some(compressed_size),
+        local_header_position: None,
+        disk_number_start: None,
+    };
+    local_file_header.set_extra_field_from_slice(&ZipExtraFieldBuilder::new().zip64(&zip64).build().unwrap()).unwrap();
+    local_file_header.compressed_size = u32::MAX;
+    local_file_header.uncompressed_size = u32::MAX;
+    let mut data_descriptor = ZipDataDescriptor::new(crc32, compressed_size, uncompressed_size, true);
+    data_descriptor.is_zip64 = true;
+    assert_eq!(data_descriptor.size(), 24);
+    local_file_header.data_descriptor = Some(data_descriptor);
+    let mut out: Vec<u8> = Vec::new();
+    local_file_header.write(&mut out).unwrap();
+    let cd_position = out.len() as u64;
+    let mut cd = ZipCDEntry::from_local_file_header(&local_file_header, cd_position).unwrap();
+    cd.write(&mut out).unwrap();
+    let mut zip_file = Cursor::new(out);
+    let output_local_header = ZipLocalFileHeader::from_central_directory(&mut zip_file, &cd).unwrap();
+    assert!(output_local_header.data_descriptor.unwrap().is_zip64);
+
+    // mismatch with the central directory
+    cd.crc32 ^= 1;
+    assert!(ZipLocalFileHeader::from_central_directory(&mut zip_file, &cd).is_err());
+}
+
+#[test_case]
+fn stream_read_test() {
+    let zip_bytes: &[u8] = include_bytes!("./assets/childrens-literature.epub");
+    let mut zip_file = Cursor::new(zip_bytes);
+    let eocd = ZipEOCD::from_reader(&mut zip_file).unwrap();
+    let cd_list = ZipCDEntry::all_from_eocd(&mut zip_file, &eocd).unwrap();
+
+    // `&[u8]` cannot seek
+    let mut stream_reader = ZipStreamReader::new(zip_bytes);
+    for cd in &cd_list {
+        assert_eq!(stream_reader.position(), cd.effective_local_header_position());
+        let local_file_header = stream_reader.next_entry().unwrap().unwrap();
+        assert_eq!(local_file_header.file_name_raw, cd.file_name_raw);
+        assert_eq!(local_file_header.decompress().unwrap().len() as u64, cd.effective_uncompressed_size());
+    }
+    // stops at the central directory
+    assert!(stream_reader.next_entry().unwrap().is_none());
+    assert!(stream_reader.is_finished());
+    assert_eq!(stream_reader.position(), eocd.effective_cd_starting_position() + 4);
+    assert!(stream_reader.next().is_none());
+
+    let stream_reader = ZipStreamReader::new(include_bytes!("./assets/ness_special_moves.txt").as_slice());
+    assert_eq!(stream_reader.map(|entry| entry.is_err()).collect::<Vec<bool>>(), [true]);
+}
+
+#[test_case]
+fn zip_crypto_test() {
+    use zip_structs::zip_error::ZipReadError;
+
+    // encrypted by Info-ZIP with password "password" (bit #3 is set, so the check byte is the high byte of the time)
+    let mut zip_file = Cursor::new(include_bytes!("./assets/zipcrypto.zip"));
+    let eocd = ZipEOCD::from_reader(&mut zip_file).unwrap();
+    let cd_list = ZipCDEntry::all_from_eocd(&mut zip_file, &eocd).unwrap();
+    assert!(cd_list.iter().all(|cd| cd.is_encrypted_data()));
+
+    let deflated = ZipLocalFileHeader::from_central_directory(&mut zip_file, &cd_list[0]).unwrap();
+    assert_eq!(
+        deflated.decompress_with_password(b"password").unwrap().as_slice(),
+        include_bytes!("./assets/ness_special_moves.txt")
+    );
+    assert!(matches!(deflated.decompress(), Err(ZipReadError::UnsupportedZipArchive { .. })));
+    assert!(matches!(deflated.decompress_with_password(b"wrong password"), Err(ZipReadError::InvalidPassword)));
+
+    let stored = ZipLocalFileHeader::from_central_directory(&mut zip_file, &cd_list[1]).unwrap();
+    assert_eq!(stored.compression_method, 0);
+    assert_eq!(stored.decompress_with_password(b"password").unwrap().as_slice(), EXPECTED_FILE_CONTENT);
+}
+
+#[test_case]
+fn zip_aes_test() {
+    use zip_structs::zip_error::ZipReadError;
+
+    // AES-256 encrypted by libarchive with password "secret"; a.txt is AE-1 (deflated) and b.txt is AE-2
+    let mut zip_file = Cursor::new(include_bytes!("./assets/aes.zip"));
+    let eocd = ZipEOCD::from_reader(&mut zip_file).unwrap();
+    let cd_list = ZipCDEntry::all_from_eocd(&mut zip_file, &eocd).unwrap();
+    assert!(cd_list.iter().all(|cd| cd.compression_method == 99));
+
+    let ae1 = ZipLocalFileHeader::from_central_directory(&mut zip_file, &cd_list[0]).unwrap();
+    assert_eq!(ae1.decompress_with_password(b"secret").unwrap(), b"hello AES world\n".repeat(50));
+    assert!(matches!(ae1.decompress_with_password(b"wrong password"), Err(ZipReadError::InvalidPassword)));
+
+    let ae2 = ZipLocalFileHeader::from_central_directory(&mut zip_file, &cd_list[1]).unwrap();
+    assert_eq!(cd_list[1].crc32, 0);
+    assert_eq!(ae2.decompress_with_password(b"secret").unwrap().as_slice(), b"short\n");
+
+    // tampered authentication code
+    let mut tampered = ZipLocalFileHeader::from_central_directory(&mut zip_file, &cd_list[1]).unwrap();
+    *tampered.compressed_data.to_mut().last_mut().unwrap() ^= 1;
+    assert!(matches!(
+        tampered.decompress_with_password(b"secret"),
+        Err(ZipReadError::AuthenticationCodeMismatch)
+    ));
+}
+
+#[test_case]
+fn multi_volume_read_test() {
+    use zip_structs::zip_archive::ZipArchive;
+
+    use alloc::vec;
+
+    // split by Info-ZIP into 64 KiB volumes; random.bin (66000 bytes) spans both volumes
+    let volumes: Vec<Cursor<&[u8]>> = vec![
+        Cursor::new(&include_bytes!("./assets/split.z01")[..]),
+        Cursor::new(&include_bytes!("./assets/split.zip")[..]),
+    ];
+    assert!(ZipArchive::new(volumes[1].clone()).is_err());
+    let archive = ZipArchive::new_multi_volume(volumes).unwrap();
+    assert_eq!(archive.len(), 2);
+    assert_eq!(archive.by_name(b"random.bin").unwrap().decompress().unwrap().len(), 66000);
+    let text = archive.by_name(b"ness_special_moves.txt").unwrap();
+    assert!(text.central_directory().effective_local_header_position() > 65536);
+    assert_eq!(text.decompress().unwrap().as_slice(), include_bytes!("./assets/ness_special_moves.txt"));
+}
+
+#[test_case]
+fn zip_bzip2_test() {
+    // `zip -Z bzip2`; repeated.txt is ness_special_moves.txt repeated 100 times
+    let mut zip_file = Cursor::new(include_bytes!("./assets/bzip2.zip"));
+    let eocd = ZipEOCD::from_reader(&mut zip_file).unwrap();
+    let cd_list = ZipCDEntry::all_from_eocd(&mut zip_file, &eocd).unwrap();
+    assert_eq!(cd_list[0].compression_method, 12);
+    let local_file_header = ZipLocalFileHeader::from_central_directory(&mut zip_file, &cd_list[0]).unwrap();
+    assert_eq!(
+        local_file_header.decompress().unwrap(),
+        include_bytes!("./assets/ness_special_moves.txt").repeat(100)
+    );
+}
+
+#[test_case]
+fn zip_bzip2_stream_end_test() {
+    use zip_structs::zip_compression::{ZipDecompressReader, COMPRESSION_METHOD_BZIP2};
+    use zip_structs::zip_error::ZipReadError;
+
+    let mut zip_file = Cursor::new(include_bytes!("./assets/bzip2.zip"));
+    let eocd = ZipEOCD::from_reader(&mut zip_file).unwrap();
+    let cd_list = ZipCDEntry::all_from_eocd(&mut zip_file, &eocd).unwrap();
+    let local_file_header = ZipLocalFileHeader::from_central_directory(&mut zip_file, &cd_list[0]).unwrap();
+    let stream: &[u8] = &local_file_header.compressed_data;
+    let expected = include_bytes!("./assets/ness_special_moves.txt").repeat(100);
+
+    // concatenated streams (e.g. by pbzip2) are decoded one after another
+    let two_streams = [stream, stream].concat();
+    let mut reader = ZipDecompressReader::new(two_streams.as_slice(), COMPRESSION_METHOD_BZIP2).unwrap();
+    assert_eq!(reader.read_all_decompressed(0).unwrap(), expected.repeat(2));
+
+    // truncated in a block or in the end-of-stream marker, or followed by garbage
+    let garbage = [stream, b"garbage"].concat();
+    for data in [&stream[..stream.len() / 2], &stream[..stream.len() - 1], garbage.as_slice()] {
+        let mut reader = ZipDecompressReader::new(data, COMPRESSION_METHOD_BZIP2).unwrap();
+        assert!(matches!(
+            reader.read_all_decompressed(0),
+            Err(ZipReadError::InvalidZipArchive { .. })
+        ));
+    }
+}
+
+#[test_case]
+fn zip_lzma_xz_test() {
+    // repeated.txt (see `zip_bzip2_test`) compressed by bsdtar (LZMA with the end-of-stream marker, and XZ),
+    // and LZMA without the end-of-stream marker (bit #1 of general purpose bit flag is not set)
+    let archives: [(&[u8], u16, u16); 3] = [
+        (include_bytes!("./assets/lzma.zip"), 14, 0x0002),
+        (include_bytes!("./assets/lzma_no_eos.zip"), 14, 0),
+        (include_bytes!("./assets/xz.zip"), 95, 0),
+    ];
+    for (archive, compression_method, lzma_flag) in archives.iter() {
+        let mut zip_file = Cursor::new(*archive);
+        let eocd = ZipEOCD::from_reader(&mut zip_file).unwrap();
+        let cd_list = ZipCDEntry::all_from_eocd(&mut zip_file, &eocd).unwrap();
+        assert_eq!(cd_list[0].compression_method, *compression_method);
+        assert_eq!(cd_list[0].general_purpose_flags & 0x0002, *lzma_flag);
+        let local_file_header = ZipLocalFileHeader::from_central_directory(&mut zip_file, &cd_list[0]).unwrap();
+        assert_eq!(
+            local_file_header.decompress().unwrap(),
+            include_bytes!("./assets/ness_special_moves.txt").repeat(100)
+        );
+    }
+}
+
+#[test_case]
+fn zip_zstd_test() {
+    // repeated.txt (see `zip_bzip2_test`) compressed by bsdtar with Zstandard
+    let mut zip_file = Cursor::new(include_bytes!("./assets/zstd.zip"));
+    let eocd = ZipEOCD::from_reader(&mut zip_file).unwrap();
+    let cd_list = ZipCDEntry::all_from_eocd(&mut zip_file, &eocd).unwrap();
+    assert_eq!(cd_list[0].compression_method, 93);
+    let local_file_header = ZipLocalFileHeader::from_central_directory(&mut zip_file, &cd_list[0]).unwrap();
+    assert_eq!(
+        local_file_header.decompress().unwrap(),
+        include_bytes!("./assets/ness_special_moves.txt").repeat(100)
+    );
+}
+
+}